@@ -0,0 +1,15 @@
+use std::env;
+
+/// Capabilities reported by the build script of librocksdb-sys, which are
+/// turned into `rocksdb_<name>` cfgs for `rocksdb::features`.
+const FEATURES: &[&str] = &["SNAPPY", "LZ4", "ZSTD", "ZLIB", "BZIP2", "JEMALLOC", "RTTI"];
+
+fn main() {
+    for feature in FEATURES {
+        let cfg = format!("rocksdb_{}", feature.to_lowercase());
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+        if env::var_os(format!("DEP_ROCKSDB_{}", feature)).is_some() {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+}
@@ -308,6 +308,9 @@ fn main() {
 
     if !try_to_find_and_link_lib("ROCKSDB") {
         build_rocksdb();
+        // The bundled build never uses jemalloc and keeps the compiler's
+        // default of RTTI enabled.
+        println!("cargo:rtti=1");
     }
     if cfg!(feature = "snappy") && !try_to_find_and_link_lib("SNAPPY") {
         build_snappy();
@@ -324,4 +327,18 @@ fn main() {
     if cfg!(feature = "bzip2") && !try_to_find_and_link_lib("BZIP2") {
         build_bzip2();
     }
+
+    // Exposed to the build scripts of dependent crates as DEP_ROCKSDB_<NAME>.
+    let codecs = [
+        ("snappy", cfg!(feature = "snappy")),
+        ("lz4", cfg!(feature = "lz4")),
+        ("zstd", cfg!(feature = "zstd")),
+        ("zlib", cfg!(feature = "zlib")),
+        ("bzip2", cfg!(feature = "bzip2")),
+    ];
+    for &(codec, enabled) in codecs.iter() {
+        if enabled {
+            println!("cargo:{}=1", codec);
+        }
+    }
 }
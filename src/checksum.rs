@@ -0,0 +1,95 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! CRC32c checksums appended to values when `Options::set_value_checksum`
+//! is enabled. The checksum covers the key followed by the value, so that a
+//! value that ends up under another key does not verify either.
+
+/// Reversed Castagnoli polynomial.
+const POLY: u32 = 0x82F6_3B78;
+
+pub const CHECKSUM_LEN: usize = 4;
+
+/// Returns the CRC32c of the concatenation of `parts`.
+pub fn crc32c(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for part in parts {
+        for &byte in part.iter() {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ POLY
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+    }
+    !crc
+}
+
+/// Returns the checksum of a value made of `value_parts` stored under the
+/// key made of `key_parts`, as 4 little-endian bytes.
+pub fn trailer(key_parts: &[&[u8]], value_parts: &[&[u8]]) -> [u8; CHECKSUM_LEN] {
+    let parts: Vec<&[u8]> = key_parts.iter().chain(value_parts).cloned().collect();
+    crc32c(&parts).to_le_bytes()
+}
+
+/// Returns `value` followed by the checksum of `key` and `value`.
+pub fn append(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut checked = Vec::with_capacity(value.len() + CHECKSUM_LEN);
+    checked.extend_from_slice(value);
+    checked.extend_from_slice(&trailer(&[key], &[value]));
+    checked
+}
+
+/// Returns the length of the original value if the checksum at the end of
+/// `checked` matches `key` and the value, or `None` otherwise.
+pub fn verify(key: &[u8], checked: &[u8]) -> Option<usize> {
+    if checked.len() < CHECKSUM_LEN {
+        return None;
+    }
+    let len = checked.len() - CHECKSUM_LEN;
+    if trailer(&[key], &[&checked[..len]])[..] == checked[len..] {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn crc32c_check_value() {
+        assert_eq!(crc32c(&[]), 0);
+        assert_eq!(crc32c(&[b"123456789"]), 0xE306_9283);
+        assert_eq!(crc32c(&[b"1234", b"", b"56789"]), 0xE306_9283);
+    }
+
+    #[test]
+    fn append_and_verify() {
+        let checked = append(b"key", b"value");
+        assert_eq!(checked.len(), 5 + CHECKSUM_LEN);
+        assert_eq!(verify(b"key", &checked), Some(5));
+        assert_eq!(verify(b"other key", &checked), None);
+
+        let mut corrupted = checked.clone();
+        corrupted[0] ^= 1;
+        assert_eq!(verify(b"key", &corrupted), None);
+        assert_eq!(verify(b"key", b"abc"), None);
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Typed access to keys and values through a user-provided `Codec`.
+//!
+//! ```
+//! use rocksdb::codec::{Codec, TypedIterator};
+//! use rocksdb::{IteratorMode, Options, DB};
+//!
+//! struct U32Codec;
+//!
+//! impl Codec<u32> for U32Codec {
+//!     type Error = String;
+//!
+//!     fn encode(&self, value: &u32) -> Vec<u8> {
+//!         value.to_be_bytes().to_vec()
+//!     }
+//!
+//!     fn decode(&self, bytes: &[u8]) -> Result<u32, String> {
+//!         if bytes.len() != 4 {
+//!             return Err(format!("expected 4 bytes, got {}", bytes.len()));
+//!         }
+//!         let mut buf = [0; 4];
+//!         buf.copy_from_slice(bytes);
+//!         Ok(u32::from_be_bytes(buf))
+//!     }
+//! }
+//!
+//! let path = "_path_for_rocksdb_storage_codec";
+//! {
+//!     let db = DB::open_default(path).unwrap();
+//!     db.put(U32Codec.encode(&1), U32Codec.encode(&10)).unwrap();
+//!     db.put(U32Codec.encode(&2), U32Codec.encode(&20)).unwrap();
+//!
+//!     let iter = TypedIterator::new(db.iterator(IteratorMode::Start).unwrap(), U32Codec);
+//!     let pairs: Vec<(u32, u32)> = iter.map(Result::unwrap).collect();
+//!     assert_eq!(pairs, vec![(1, 10), (2, 20)]);
+//! }
+//! let _ = DB::destroy(&Options::default(), path);
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use {DBIterator, Error};
+
+/// Converts values of type `T` to and from the bytes stored in the database.
+///
+/// A single codec type can implement `Codec` for both the key and the value
+/// type of a `TypedIterator`.
+pub trait Codec<T> {
+    type Error: fmt::Display;
+
+    fn encode(&self, value: &T) -> Vec<u8>;
+
+    fn decode(&self, bytes: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// An iterator which decodes the keys and values of a `DBIterator` with a
+/// `Codec`.
+///
+/// Every item is decoded independently: an entry that fails to decode is
+/// returned as an `Err` and iteration carries on with the next entry. Use
+/// e.g. `take_while` or `collect::<Result<Vec<_>, _>>()` to stop at the
+/// first error instead. If the underlying iterator stops because of an
+/// error, that error is returned as the last item.
+pub struct TypedIterator<'a, K, V, C> {
+    inner: DBIterator<'a>,
+    codec: C,
+    finished: bool,
+    types: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, K, V, C> TypedIterator<'a, K, V, C>
+where
+    C: Codec<K> + Codec<V>,
+{
+    pub fn new(inner: DBIterator<'a>, codec: C) -> TypedIterator<'a, K, V, C> {
+        TypedIterator {
+            inner,
+            codec,
+            finished: false,
+            types: PhantomData,
+        }
+    }
+
+    fn decode(&self, key: &[u8], value: &[u8]) -> Result<(K, V), Error> {
+        let key = Codec::<K>::decode(&self.codec, key)
+            .map_err(|e| Error::new(format!("Failed to decode key: {}", e)))?;
+        let value = Codec::<V>::decode(&self.codec, value)
+            .map_err(|e| Error::new(format!("Failed to decode value: {}", e)))?;
+        Ok((key, value))
+    }
+}
+
+impl<'a, K, V, C> Iterator for TypedIterator<'a, K, V, C>
+where
+    C: Codec<K> + Codec<V>,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Result<(K, V), Error>> {
+        if self.finished {
+            return None;
+        }
+        match self.inner.next() {
+            Some((key, value)) => Some(self.decode(&key, &value)),
+            None => {
+                self.finished = true;
+                self.inner.status().err().map(Err)
+            }
+        }
+    }
+}
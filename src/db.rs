@@ -13,21 +13,41 @@
 // limitations under the License.
 //
 
+use checkpoint::Checkpoint;
+use checksum;
 use ffi;
-use ffi_util::opt_bytes_to_ptr;
-use {ColumnFamily, ColumnFamilyDescriptor, Error, FlushOptions, Options, WriteOptions, DB};
+use ffi_util::{error_message, opt_bytes_to_ptr};
+use metrics::{self, Metric};
+use {
+    BottommostLevelCompaction, ColumnFamily, ColumnFamilyDescriptor, CompactOptions, Error,
+    ErrorKind, FlushOptions, IngestExternalFileOptions, Options, WriteOptions, DB,
+};
 
 use libc::{self, c_char, c_int, c_uchar, c_void, size_t};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cmp;
 use std::collections::BTreeMap;
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::fs;
 use std::marker::PhantomData;
-use std::ops::Deref;
-use std::path::Path;
+use std::mem::ManuallyDrop;
+use std::ops::{ControlFlow, Deref};
+use std::path::{Path, PathBuf};
 use std::ptr;
 use std::slice;
 use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long `DB::try_open` waits between attempts to acquire the lock.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long `DB::settle` waits between checks for background work.
+const SETTLE_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 unsafe impl Send for DB {}
 unsafe impl Sync for DB {}
@@ -58,6 +78,21 @@ pub enum DBRecoveryMode {
     SkipAnyCorruptedRecord = ffi::rocksdb_skip_any_corrupted_records_recovery as isize,
 }
 
+/// Which storage tiers a read is allowed to reach; see `ReadOptions::set_read_tier`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReadTier {
+    /// Read from memtables, the block cache and disk.
+    All = 0,
+    /// Only read from memtables and the block cache. Reads that would need IO
+    /// fail with an `Incomplete` status instead.
+    BlockCache = 1,
+    /// Only read persisted data, skipping memtable entries that were written
+    /// with the WAL disabled. Only supported by point lookups.
+    Persisted = 2,
+    /// Only read from memtables. Only supported by iterators.
+    Memtable = 3,
+}
+
 /// An atomic batch of write operations.
 ///
 /// Making an atomic commit of several writes:
@@ -78,6 +113,36 @@ pub enum DBRecoveryMode {
 /// ```
 pub struct WriteBatch {
     inner: *mut ffi::rocksdb_writebatch_t,
+    has_values: bool,
+    value_checksum: bool,
+}
+
+/// A write batch that also keeps an index of its updates, so they can be
+/// read back before the batch is written to the database.
+///
+/// ```
+/// use rocksdb::{DB, Options, WriteBatchWithIndex};
+///
+/// let path = "_path_for_rocksdb_storage_wbwi";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     db.put(b"key1", b"value1").unwrap();
+///
+///     let mut batch = WriteBatchWithIndex::new(0, true);
+///     batch.put(b"key2", b"value2").unwrap();
+///     batch.delete(b"key1").unwrap();
+///     assert!(batch.get_from_batch_and_db(&db, b"key1").unwrap().is_none());
+///     assert!(db.get(b"key1").unwrap().is_some());
+///
+///     db.write_wbwi(&batch).unwrap(); // Atomically commits the batch
+///     assert!(db.get(b"key1").unwrap().is_none());
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct WriteBatchWithIndex {
+    inner: *mut ffi::rocksdb_writebatch_wi_t,
+    has_values: bool,
+    value_checksum: bool,
 }
 
 pub struct ReadOptions {
@@ -93,7 +158,7 @@ pub struct ReadOptions {
 /// {
 ///     let db = DB::open_default(path).unwrap();
 ///     let snapshot = db.snapshot(); // Creates a longer-term snapshot of the DB, but closed when goes out of scope
-///     let mut iter = snapshot.iterator(IteratorMode::Start); // Make as many iterators as you'd like from one snapshot
+///     let mut iter = snapshot.iterator(IteratorMode::Start).unwrap(); // Make as many iterators as you'd like from one snapshot
 /// }
 /// let _ = DB::destroy(&Options::default(), path);
 /// ```
@@ -122,7 +187,7 @@ unsafe impl<'a> Sync for Snapshot<'a> {}
 /// let path = "_path_for_rocksdb_storage4";
 /// {
 ///     let db = DB::open_default(path).unwrap();
-///     let mut iter = db.raw_iterator();
+///     let mut iter = db.raw_iterator().unwrap();
 ///
 ///     // Forwards iteration
 ///     iter.seek_to_first();
@@ -158,9 +223,62 @@ unsafe impl<'a> Sync for Snapshot<'a> {}
 /// ```
 pub struct DBRawIterator<'a> {
     inner: *mut ffi::rocksdb_iterator_t,
+    _slot: IteratorSlot,
+    /// The default read options of the database, if the iterator was opened
+    /// with them. RocksDB keeps pointers into them, so they must outlive the
+    /// iterator even if the defaults are replaced in the meantime.
+    readopts: Option<Arc<ReadOptions>>,
+    value_checksum: bool,
+    checksum_mismatch: Cell<bool>,
     db: PhantomData<&'a DB>,
 }
 
+/// The function set with `DB::set_write_observer`.
+pub type WriteObserver = Arc<dyn Fn(&WriteBatch) + Send + Sync>;
+
+/// Passes the single update added by `update` to `observer` as a batch.
+fn notify_one<F>(observer: &WriteObserver, update: F)
+where
+    F: FnOnce(&mut WriteBatch) -> Result<(), Error>,
+{
+    let mut batch = WriteBatch::default();
+    // Adding an update to a plain batch cannot fail.
+    let _ = update(&mut batch);
+    observer(&batch);
+}
+
+/// Counts the iterators open on a database, for `DB::set_max_open_iterators`.
+#[derive(Default)]
+pub struct IteratorLimit {
+    open: AtomicUsize,
+    max: AtomicUsize,
+}
+
+/// Held by every open iterator, and releases its place in the
+/// `IteratorLimit` when dropped.
+struct IteratorSlot(Arc<IteratorLimit>);
+
+impl IteratorSlot {
+    fn acquire(limit: &Arc<IteratorLimit>) -> Result<IteratorSlot, Error> {
+        let open = limit.open.fetch_add(1, Ordering::SeqCst) + 1;
+        let max = limit.max.load(Ordering::SeqCst);
+        if max > 0 && open > max {
+            limit.open.fetch_sub(1, Ordering::SeqCst);
+            return Err(Error::new(format!(
+                "Cannot open more than {} iterators at once",
+                max
+            )));
+        }
+        Ok(IteratorSlot(limit.clone()))
+    }
+}
+
+impl Drop for IteratorSlot {
+    fn drop(&mut self) {
+        self.0.open.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// An iterator over a database or column family, with specifiable
 /// ranges and direction.
 ///
@@ -170,21 +288,21 @@ pub struct DBRawIterator<'a> {
 /// let path = "_path_for_rocksdb_storage2";
 /// {
 ///     let db = DB::open_default(path).unwrap();
-///     let mut iter = db.iterator(IteratorMode::Start); // Always iterates forward
+///     let mut iter = db.iterator(IteratorMode::Start).unwrap(); // Always iterates forward
 ///     for (key, value) in iter {
 ///         println!("Saw {:?} {:?}", key, value);
 ///     }
-///     iter = db.iterator(IteratorMode::End);  // Always iterates backward
+///     iter = db.iterator(IteratorMode::End).unwrap();  // Always iterates backward
 ///     for (key, value) in iter {
 ///         println!("Saw {:?} {:?}", key, value);
 ///     }
-///     iter = db.iterator(IteratorMode::From(b"my key", Direction::Forward)); // From a key in Direction::{forward,reverse}
+///     iter = db.iterator(IteratorMode::From(b"my key", Direction::Forward)).unwrap(); // From a key in Direction::{forward,reverse}
 ///     for (key, value) in iter {
 ///         println!("Saw {:?} {:?}", key, value);
 ///     }
 ///
 ///     // You can seek with an existing Iterator instance, too
-///     iter = db.iterator(IteratorMode::Start);
+///     iter = db.iterator(IteratorMode::Start).unwrap();
 ///     iter.set_mode(IteratorMode::From(b"another key", Direction::Reverse));
 ///     for (key, value) in iter {
 ///         println!("Saw {:?} {:?}", key, value);
@@ -208,16 +326,27 @@ pub type KVBytes = (Box<[u8]>, Box<[u8]>);
 pub enum IteratorMode<'a> {
     Start,
     End,
+    /// Starts at the given key, or the key next to it if it does not exist.
+    ///
+    /// With `Direction::Forward` the iterator seeks to the first key at or
+    /// after the given key, and with `Direction::Reverse` to the last key at
+    /// or before it, like `DBRawIterator::seek_for_prev`. The latter answers
+    /// "largest key <= target" queries with a single call to `next`.
     From(&'a [u8], Direction),
 }
 
 impl<'a> DBRawIterator<'a> {
-    fn new(db: &DB, readopts: &ReadOptions) -> DBRawIterator<'a> {
+    fn new(db: &DB, readopts: &ReadOptions) -> Result<DBRawIterator<'a>, Error> {
+        let slot = IteratorSlot::acquire(&db.open_iterators)?;
         unsafe {
-            DBRawIterator {
+            Ok(DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(db.inner, readopts.inner),
+                _slot: slot,
+                readopts: None,
+                value_checksum: db.value_checksum,
+                checksum_mismatch: Cell::new(false),
                 db: PhantomData,
-            }
+            })
         }
     }
 
@@ -226,9 +355,14 @@ impl<'a> DBRawIterator<'a> {
         cf_handle: &ColumnFamily,
         readopts: &ReadOptions,
     ) -> Result<DBRawIterator<'a>, Error> {
+        let slot = IteratorSlot::acquire(&db.open_iterators)?;
         unsafe {
             Ok(DBRawIterator {
                 inner: ffi::rocksdb_create_iterator_cf(db.inner, readopts.inner, cf_handle.inner),
+                _slot: slot,
+                readopts: None,
+                value_checksum: db.value_checksum,
+                checksum_mismatch: Cell::new(false),
                 db: PhantomData,
             })
         }
@@ -253,6 +387,9 @@ impl<'a> DBRawIterator<'a> {
         unsafe {
             ffi_try!(ffi::rocksdb_iter_get_error(self.inner,));
         }
+        if self.checksum_mismatch.get() {
+            return Err(checksum_mismatch());
+        }
         Ok(())
     }
 
@@ -266,7 +403,7 @@ impl<'a> DBRawIterator<'a> {
     /// let path = "_path_for_rocksdb_storage5";
     /// {
     ///     let db = DB::open_default(path).unwrap();
-    ///     let mut iter = db.raw_iterator();
+    ///     let mut iter = db.raw_iterator().unwrap();
     ///
     ///     // Iterate all keys from the start in lexicographic order
     ///     iter.seek_to_first();
@@ -303,7 +440,7 @@ impl<'a> DBRawIterator<'a> {
     /// let path = "_path_for_rocksdb_storage6";
     /// {
     ///     let db = DB::open_default(path).unwrap();
-    ///     let mut iter = db.raw_iterator();
+    ///     let mut iter = db.raw_iterator().unwrap();
     ///
     ///     // Iterate all keys from the end in reverse lexicographic order
     ///     iter.seek_to_last();
@@ -343,7 +480,7 @@ impl<'a> DBRawIterator<'a> {
     /// let path = "_path_for_rocksdb_storage7";
     /// {
     ///     let db = DB::open_default(path).unwrap();
-    ///     let mut iter = db.raw_iterator();
+    ///     let mut iter = db.raw_iterator().unwrap();
     ///
     ///     // Read the first key that starts with 'a'
     ///     iter.seek(b"a");
@@ -382,7 +519,7 @@ impl<'a> DBRawIterator<'a> {
     /// let path = "_path_for_rocksdb_storage8";
     /// {
     ///     let db = DB::open_default(path).unwrap();
-    ///     let mut iter = db.raw_iterator();
+    ///     let mut iter = db.raw_iterator().unwrap();
     ///
     ///     // Read the last key that starts with 'a'
     ///     iter.seek_for_prev(b"b");
@@ -461,8 +598,16 @@ impl<'a> DBRawIterator<'a> {
             let mut val_len: size_t = 0;
             let val_len_ptr: *mut size_t = &mut val_len;
             let val_ptr = ffi::rocksdb_iter_value(self.inner, val_len_ptr) as *const c_uchar;
+            let value = slice::from_raw_parts(val_ptr, val_len as usize);
 
-            Some(slice::from_raw_parts(val_ptr, val_len as usize))
+            if self.value_checksum {
+                let key = self.key_inner().unwrap_or(&[]);
+                match checksum::verify(key, value) {
+                    Some(len) => return Some(&value[..len]),
+                    None => self.checksum_mismatch.set(true),
+                }
+            }
+            Some(value)
         } else {
             None
         }
@@ -483,9 +628,16 @@ impl<'a> Drop for DBRawIterator<'a> {
 }
 
 impl<'a> DBIterator<'a> {
-    fn new(db: &DB, readopts: &ReadOptions, mode: IteratorMode) -> DBIterator<'a> {
+    fn new(db: &DB, readopts: &ReadOptions, mode: IteratorMode) -> Result<DBIterator<'a>, Error> {
+        Ok(DBIterator::from_raw(
+            DBRawIterator::new(db, readopts)?,
+            mode,
+        ))
+    }
+
+    fn from_raw(raw: DBRawIterator<'a>, mode: IteratorMode) -> DBIterator<'a> {
         let mut rv = DBIterator {
-            raw: DBRawIterator::new(db, readopts),
+            raw,
             direction: Direction::Forward, // blown away by set_mode()
             just_seeked: false,
         };
@@ -575,6 +727,316 @@ impl<'a> Into<DBRawIterator<'a>> for DBIterator<'a> {
     }
 }
 
+/// A builder for iterators which need more than the default read options,
+/// created with [`DB::scan`](DB::scan).
+///
+/// Unlike `ReadOptions`, the builder owns its bounds, so the resulting
+/// `ScanIterator` can be used without any lifetime caveats.
+///
+/// ```
+/// use rocksdb::{DB, IteratorMode, Options};
+///
+/// let path = "_path_for_rocksdb_storage_scan";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     db.put(b"a1", b"1").unwrap();
+///     db.put(b"b1", b"2").unwrap();
+///     db.put(b"c1", b"3").unwrap();
+///     let snapshot = db.snapshot();
+///     let iter = db
+///         .scan()
+///         .lower_bound(b"b")
+///         .upper_bound(b"c")
+///         .fill_cache(false)
+///         .snapshot(&snapshot)
+///         .iterator(IteratorMode::Start)
+///         .unwrap();
+///     assert_eq!(iter.count(), 1);
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct ScanBuilder<'a> {
+    db: &'a DB,
+    cf: Option<&'a ColumnFamily>,
+    snapshot: Option<&'a Snapshot<'a>>,
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+    prefix_same_as_start: bool,
+    total_order_seek: bool,
+    fill_cache: bool,
+    read_tier: ReadTier,
+}
+
+impl<'a> ScanBuilder<'a> {
+    fn new(db: &'a DB) -> ScanBuilder<'a> {
+        ScanBuilder {
+            db,
+            cf: None,
+            snapshot: None,
+            lower_bound: None,
+            upper_bound: None,
+            prefix_same_as_start: false,
+            total_order_seek: false,
+            fill_cache: true,
+            read_tier: ReadTier::All,
+        }
+    }
+
+    /// Scan the given column family instead of the default one.
+    pub fn cf(mut self, cf: &'a ColumnFamily) -> Self {
+        self.cf = Some(cf);
+        self
+    }
+
+    /// Read as of the given snapshot.
+    pub fn snapshot(mut self, snapshot: &'a Snapshot<'a>) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// Only return keys greater than or equal to `key`.
+    pub fn lower_bound<K: AsRef<[u8]>>(mut self, key: K) -> Self {
+        self.lower_bound = Some(key.as_ref().to_vec());
+        self
+    }
+
+    /// Only return keys strictly less than `key`.
+    pub fn upper_bound<K: AsRef<[u8]>>(mut self, key: K) -> Self {
+        self.upper_bound = Some(key.as_ref().to_vec());
+        self
+    }
+
+    /// See `ReadOptions::set_prefix_same_as_start`.
+    pub fn prefix_same_as_start(mut self, v: bool) -> Self {
+        self.prefix_same_as_start = v;
+        self
+    }
+
+    /// See `ReadOptions::set_total_order_seek`.
+    pub fn total_order_seek(mut self, v: bool) -> Self {
+        self.total_order_seek = v;
+        self
+    }
+
+    /// See `ReadOptions::fill_cache`.
+    pub fn fill_cache(mut self, v: bool) -> Self {
+        self.fill_cache = v;
+        self
+    }
+
+    /// See `ReadOptions::set_read_tier`.
+    pub fn read_tier(mut self, tier: ReadTier) -> Self {
+        self.read_tier = tier;
+        self
+    }
+
+    /// Create the iterator, positioned according to `mode`.
+    pub fn iterator(self, mode: IteratorMode) -> Result<ScanIterator<'a>, Error> {
+        let mut readopts = ReadOptions::default();
+        readopts.set_prefix_same_as_start(self.prefix_same_as_start);
+        readopts.set_total_order_seek(self.total_order_seek);
+        readopts.fill_cache(self.fill_cache);
+        readopts.set_read_tier(self.read_tier);
+        if let Some(snapshot) = self.snapshot {
+            readopts.set_snapshot(snapshot);
+        }
+        // The bounds are moved into the returned `ScanIterator` together with
+        // `readopts`; moving a `Vec` does not move its heap buffer.
+        unsafe {
+            if let Some(ref key) = self.lower_bound {
+                readopts.set_iterate_lower_bound(key);
+            }
+            if let Some(ref key) = self.upper_bound {
+                readopts.set_iterate_upper_bound(key);
+            }
+        }
+
+        let iter = match self.cf {
+            Some(cf) => DBIterator::new_cf(self.db, cf, &readopts, mode)?,
+            None => DBIterator::new(self.db, &readopts, mode)?,
+        };
+        Ok(ScanIterator {
+            iter,
+            _readopts: readopts,
+            _lower_bound: self.lower_bound,
+            _upper_bound: self.upper_bound,
+        })
+    }
+}
+
+/// An iterator created by a [`ScanBuilder`](ScanBuilder), which keeps the
+/// read options and bounds it was created with alive.
+pub struct ScanIterator<'a> {
+    // Fields are dropped in declaration order: the iterator must go first.
+    iter: DBIterator<'a>,
+    _readopts: ReadOptions,
+    _lower_bound: Option<Vec<u8>>,
+    _upper_bound: Option<Vec<u8>>,
+}
+
+impl<'a> ScanIterator<'a> {
+    /// Returns an error if the scan stopped because the underlying iterator
+    /// encountered one. See [`DBRawIterator::status`](DBRawIterator::status).
+    pub fn status(&self) -> Result<(), Error> {
+        self.iter.status()
+    }
+}
+
+impl<'a> Iterator for ScanIterator<'a> {
+    type Item = KVBytes;
+
+    fn next(&mut self) -> Option<KVBytes> {
+        self.iter.next()
+    }
+}
+
+/// An iterator over the keys starting with a prefix which returns at most a
+/// given number of them, created with
+/// [`DB::prefix_iterator_capped`](DB::prefix_iterator_capped).
+pub struct CappedPrefixIterator<'a> {
+    raw: DBRawIterator<'a>,
+    prefix: Vec<u8>,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl<'a> CappedPrefixIterator<'a> {
+    fn new(
+        mut raw: DBRawIterator<'a>,
+        prefix: &[u8],
+        max_items: usize,
+    ) -> CappedPrefixIterator<'a> {
+        raw.seek(prefix);
+        CappedPrefixIterator {
+            raw,
+            prefix: prefix.to_vec(),
+            remaining: max_items,
+            truncated: false,
+        }
+    }
+
+    /// Returns `true` once the iterator stopped at the cap while more keys
+    /// with the prefix were left.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// See [`DBRawIterator::status`](DBRawIterator::status).
+    pub fn status(&self) -> Result<(), Error> {
+        self.raw.status()
+    }
+}
+
+impl<'a> Iterator for CappedPrefixIterator<'a> {
+    type Item = KVBytes;
+
+    fn next(&mut self) -> Option<KVBytes> {
+        let key = match self.raw.key() {
+            Some(key) if key.starts_with(&self.prefix) => key,
+            _ => return None,
+        };
+        if self.remaining == 0 {
+            self.truncated = true;
+            return None;
+        }
+        self.remaining -= 1;
+        let value = self.raw.value().unwrap_or_default();
+        self.raw.next();
+        Some((key.into_boxed_slice(), value.into_boxed_slice()))
+    }
+}
+
+/// Builds a new database from SST files in one go: the database is opened
+/// with `Options::prepare_for_bulk_load`, the files are ingested, the data
+/// is compacted and, once all background work has finished, the database
+/// is reopened with the regular options and returned, ready to serve.
+///
+/// Only the default column family is loaded.
+///
+/// ```
+/// use rocksdb::{BulkLoader, Options, SstFileWriter, DB};
+///
+/// let path = "_path_for_rocksdb_storage_bulk_loader";
+/// let sst_path = "_path_for_rocksdb_storage_bulk_loader.sst";
+/// {
+///     let opts = Options::default();
+///     let mut writer = SstFileWriter::create(&opts);
+///     writer.open(sst_path).unwrap();
+///     writer.put(b"k1", b"v1").unwrap();
+///     writer.finish().unwrap();
+///
+///     let db = BulkLoader::new(path)
+///         .with_options(|opts| opts.set_max_open_files(100))
+///         .ingest(vec![sst_path])
+///         .finish()
+///         .unwrap();
+///     assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// let _ = std::fs::remove_file(sst_path);
+/// ```
+pub struct BulkLoader<F = fn(&mut Options)> {
+    path: PathBuf,
+    configure: F,
+    files: Vec<PathBuf>,
+}
+
+impl BulkLoader {
+    pub fn new<P: AsRef<Path>>(path: P) -> BulkLoader {
+        fn no_options(_: &mut Options) {}
+
+        BulkLoader {
+            path: path.as_ref().to_path_buf(),
+            configure: no_options,
+            files: Vec::new(),
+        }
+    }
+}
+
+impl<F: Fn(&mut Options)> BulkLoader<F> {
+    /// Sets the function configuring the options of the database. It is
+    /// called twice: once for the options used while loading, which are
+    /// then adjusted for bulk loading, and once for the serving options.
+    pub fn with_options<G: Fn(&mut Options)>(self, configure: G) -> BulkLoader<G> {
+        BulkLoader {
+            path: self.path,
+            configure,
+            files: self.files,
+        }
+    }
+
+    /// Adds SST files, created with `SstFileWriter`, to be ingested.
+    pub fn ingest<I, P>(mut self, paths: I) -> BulkLoader<F>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.files
+            .extend(paths.into_iter().map(|p| p.as_ref().to_path_buf()));
+        self
+    }
+
+    pub fn finish(self) -> Result<DB, Error> {
+        {
+            let mut opts = Options::default();
+            (self.configure)(&mut opts);
+            opts.create_if_missing(true);
+            opts.prepare_for_bulk_load();
+
+            let db = DB::open(&opts, &self.path)?;
+            if !self.files.is_empty() {
+                db.ingest_external_file(self.files)?;
+            }
+            db.compact_range(None::<&[u8]>, None::<&[u8]>);
+            db.settle()?;
+        }
+
+        let mut opts = Options::default();
+        (self.configure)(&mut opts);
+        DB::open(&opts, &self.path)
+    }
+}
+
 impl<'a> Snapshot<'a> {
     pub fn new(db: &DB) -> Snapshot {
         let snapshot = unsafe { ffi::rocksdb_create_snapshot(db.inner) };
@@ -584,11 +1046,13 @@ impl<'a> Snapshot<'a> {
         }
     }
 
-    pub fn iterator(&self, mode: IteratorMode) -> DBIterator {
+    /// Opens an iterator over the data in this snapshot, using the default read options.
+    pub fn iterator(&self, mode: IteratorMode) -> Result<DBIterator, Error> {
         let readopts = ReadOptions::default();
         self.iterator_opt(mode, readopts)
     }
 
+    /// Opens an iterator over the data in this snapshot under the given column family, using the default read options.
     pub fn iterator_cf(
         &self,
         cf_handle: &ColumnFamily,
@@ -598,7 +1062,12 @@ impl<'a> Snapshot<'a> {
         self.iterator_cf_opt(cf_handle, readopts, mode)
     }
 
-    pub fn iterator_opt(&self, mode: IteratorMode, mut readopts: ReadOptions) -> DBIterator {
+    /// Opens an iterator over the data in this snapshot, using the given read options.
+    pub fn iterator_opt(
+        &self,
+        mode: IteratorMode,
+        mut readopts: ReadOptions,
+    ) -> Result<DBIterator, Error> {
         readopts.set_snapshot(self);
         DBIterator::new(self.db, &readopts, mode)
     }
@@ -614,7 +1083,7 @@ impl<'a> Snapshot<'a> {
     }
 
     /// Opens a raw iterator over the data in this snapshot, using the default read options.
-    pub fn raw_iterator(&self) -> DBRawIterator {
+    pub fn raw_iterator(&self) -> Result<DBRawIterator, Error> {
         let readopts = ReadOptions::default();
         self.raw_iterator_opt(readopts)
     }
@@ -626,7 +1095,7 @@ impl<'a> Snapshot<'a> {
     }
 
     /// Opens a raw iterator over the data in this snapshot, using the given read options.
-    pub fn raw_iterator_opt(&self, mut readopts: ReadOptions) -> DBRawIterator {
+    pub fn raw_iterator_opt(&self, mut readopts: ReadOptions) -> Result<DBRawIterator, Error> {
         readopts.set_snapshot(self);
         DBRawIterator::new(self.db, &readopts)
     }
@@ -646,6 +1115,7 @@ impl<'a> Snapshot<'a> {
         self.get_opt(key, readopts)
     }
 
+    /// Returns the value of `key` in the given column family as of this snapshot, using the default read options.
     pub fn get_cf<K: AsRef<[u8]>>(
         &self,
         cf: &ColumnFamily,
@@ -683,6 +1153,111 @@ impl<'a> Drop for Snapshot<'a> {
     }
 }
 
+/// Identifies a snapshot in a `SnapshotSet`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotId(u64);
+
+/// Keeps track of several snapshots of a database, e.g. one for each
+/// long-running query, so they can be released together and the oldest one
+/// can be found.
+///
+/// A snapshot keeps RocksDB from discarding the data it can see, so an old
+/// snapshot that is never released keeps obsolete files around. Comparing
+/// `oldest_sequence` with `DB::latest_sequence_number` shows how far behind
+/// the oldest snapshot is.
+///
+/// ```
+/// use rocksdb::{Options, SnapshotSet, DB};
+///
+/// let path = "_path_for_rocksdb_storage_snapshot_set";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     let mut snapshots = SnapshotSet::new(&db);
+///     db.put(b"k1", b"v1").unwrap();
+///     let id = snapshots.create();
+///     db.put(b"k1", b"v2").unwrap();
+///
+///     let snapshot = snapshots.get(id).unwrap();
+///     assert_eq!(&*snapshot.get(b"k1").unwrap().unwrap(), b"v1");
+///     assert_eq!(snapshots.oldest_sequence(), Some(1));
+///     snapshots.release_all();
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct SnapshotSet<'a> {
+    db: &'a DB,
+    snapshots: BTreeMap<SnapshotId, (u64, Snapshot<'a>)>,
+    next_id: u64,
+}
+
+impl<'a> SnapshotSet<'a> {
+    pub fn new(db: &'a DB) -> SnapshotSet<'a> {
+        SnapshotSet {
+            db,
+            snapshots: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Takes a new snapshot and adds it to the set.
+    pub fn create(&mut self) -> SnapshotId {
+        let sequence = self.db.latest_sequence_number();
+        let snapshot = Snapshot::new(self.db);
+        let id = SnapshotId(self.next_id);
+        self.next_id += 1;
+        self.snapshots.insert(id, (sequence, snapshot));
+        id
+    }
+
+    pub fn get(&self, id: SnapshotId) -> Option<&Snapshot<'a>> {
+        self.snapshots.get(&id).map(|&(_, ref snapshot)| snapshot)
+    }
+
+    /// The latest sequence number when the snapshot was taken. The snapshot
+    /// sees at least all writes up to this sequence number, and possibly
+    /// writes that raced with its creation.
+    pub fn sequence(&self, id: SnapshotId) -> Option<u64> {
+        self.snapshots.get(&id).map(|&(sequence, _)| sequence)
+    }
+
+    /// The sequence number of the oldest snapshot in the set, if any.
+    pub fn oldest_sequence(&self) -> Option<u64> {
+        self.snapshots.values().map(|&(sequence, _)| sequence).min()
+    }
+
+    /// Releases a snapshot. Returns `false` if it was not in the set.
+    pub fn release(&mut self, id: SnapshotId) -> bool {
+        self.snapshots.remove(&id).is_some()
+    }
+
+    /// Releases all snapshots in the set.
+    pub fn release_all(&mut self) {
+        self.snapshots.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+impl ColumnFamily {
+    /// Returns the underlying `rocksdb_column_family_handle_t` pointer, for
+    /// use with the `librocksdb-sys` C API.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid as long as this `ColumnFamily` is, i.e. until
+    /// the column family is dropped or the owning `DB` is closed. The handle is
+    /// still owned by the `DB` and must not be destroyed by the caller.
+    pub unsafe fn as_raw_ptr(&self) -> *mut ffi::rocksdb_column_family_handle_t {
+        self.inner
+    }
+}
+
 impl ColumnFamilyDescriptor {
     // Create a new column family descriptor with the specified name and options.
     pub fn new<S>(name: S, options: Options) -> Self
@@ -709,6 +1284,58 @@ impl DB {
         DB::open_cf(opts, path, None::<&str>)
     }
 
+    /// Open the database with the specified options, wrapped in an `Arc` so
+    /// it can be shared between threads.
+    pub fn open_shared<P: AsRef<Path>>(opts: &Options, path: P) -> Result<Arc<DB>, Error> {
+        DB::open(opts, path).map(Arc::new)
+    }
+
+    /// Open the database with the specified options, retrying for up to
+    /// `timeout` while another `DB` instance holds the database lock.
+    ///
+    /// Returns an error of kind `ErrorKind::LockTimeout` if the lock could not
+    /// be acquired in time. Any other open error is returned immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{DB, ErrorKind, Options};
+    /// use std::time::Duration;
+    ///
+    /// let path = "_path_for_rocksdb_storage_try_open";
+    /// {
+    ///     let mut opts = Options::default();
+    ///     opts.create_if_missing(true);
+    ///     let _db = DB::try_open(&opts, path, Duration::from_secs(1)).unwrap();
+    ///     let second = DB::try_open(&opts, path, Duration::from_millis(50));
+    ///     assert_eq!(second.err().unwrap().kind(), ErrorKind::LockTimeout);
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn try_open<P: AsRef<Path>>(
+        opts: &Options,
+        path: P,
+        timeout: Duration,
+    ) -> Result<DB, Error> {
+        let path = path.as_ref();
+        let start = Instant::now();
+        loop {
+            match DB::open(opts, path) {
+                Err(ref e) if is_lock_error(e) => {
+                    let elapsed = start.elapsed();
+                    if elapsed >= timeout {
+                        return Err(Error::with_kind(
+                            ErrorKind::LockTimeout,
+                            format!("Timed out after {:?} waiting for DB lock: {}", timeout, e),
+                        ));
+                    }
+                    thread::sleep(cmp::min(LOCK_RETRY_INTERVAL, timeout - elapsed));
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Open a database with the given database options and column family names.
     ///
     /// Column families opened using this function will be created with default `Options`.
@@ -815,10 +1442,22 @@ impl DB {
             return Err(Error::new("Could not initialize database.".to_owned()));
         }
 
+        let mut default_write_opts = WriteOptions::default();
+        default_write_opts.disable_wal(opts.disable_wal);
+
         Ok(DB {
             inner: db,
             cfs: cf_map,
             path: path.to_path_buf(),
+            max_value_size: opts.max_value_size,
+            value_checksum: opts.value_checksum,
+            open_iterators: Arc::new(IteratorLimit::default()),
+            default_read_opts: RwLock::new(Arc::new(ReadOptions::default())),
+            default_write_opts: RwLock::new(Arc::new(default_write_opts)),
+            monotonic_prefix_len: opts.monotonic_prefix_len,
+            last_prefixes: Mutex::new(BTreeMap::new()),
+            write_observer: RwLock::new(None),
+            observed_writes: Mutex::new(()),
         })
     }
 
@@ -862,6 +1501,49 @@ impl DB {
         &self.path.as_path()
     }
 
+    /// Returns the underlying `rocksdb_t` pointer, for use with the
+    /// `librocksdb-sys` C API or libraries built on top of it.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid for as long as this `DB` is alive and must
+    /// not outlive it. The database is still owned by this `DB`: the caller
+    /// must not close it or use the pointer concurrently with `close`/`drop`.
+    pub unsafe fn as_raw_ptr(&self) -> *mut ffi::rocksdb_t {
+        self.inner
+    }
+
+    /// Wraps a `rocksdb_t` pointer opened elsewhere (e.g. by a C or C++
+    /// component linked into the same process) so that it can be used through
+    /// this API, without taking ownership of it.
+    ///
+    /// The returned `DB` is wrapped in `ManuallyDrop`, so dropping it never
+    /// closes the database. Column families opened by the other component are
+    /// not known to the wrapper, and `path()` returns an empty path.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an open database that stays open for as long as
+    /// the returned value (and anything borrowed from it, such as snapshots or
+    /// iterators) is in use. The caller must not call `ManuallyDrop::drop` or
+    /// `ManuallyDrop::into_inner` on the result.
+    pub unsafe fn from_raw_borrowed(ptr: *mut ffi::rocksdb_t) -> ManuallyDrop<DB> {
+        ManuallyDrop::new(DB {
+            inner: ptr,
+            cfs: BTreeMap::new(),
+            path: PathBuf::new(),
+            max_value_size: 0,
+            value_checksum: false,
+            open_iterators: Arc::new(IteratorLimit::default()),
+            default_read_opts: RwLock::new(Arc::new(ReadOptions::default())),
+            default_write_opts: RwLock::new(Arc::new(WriteOptions::default())),
+            monotonic_prefix_len: 0,
+            last_prefixes: Mutex::new(BTreeMap::new()),
+            write_observer: RwLock::new(None),
+            observed_writes: Mutex::new(()),
+        })
+    }
+
     /// Flush database memtable to SST files on disk (with options).
     pub fn flush_opt(&self, flushopts: &FlushOptions) -> Result<(), Error> {
         unsafe {
@@ -875,40 +1557,174 @@ impl DB {
         self.flush_opt(&FlushOptions::default())
     }
 
-    pub fn write_opt(&self, batch: WriteBatch, writeopts: &WriteOptions) -> Result<(), Error> {
+    /// Flush database memtable to SST files on disk, and return the sequence
+    /// number of the last write contained in the flush.
+    ///
+    /// Replication can start from a copy of the flushed files and then apply
+    /// the changes returned by `get_updates_since(sequence + 1)`. RocksDB
+    /// cannot capture the sequence number atomically with the flush, so it
+    /// is read just before flushing: a write that races with this call may
+    /// end up in the flushed files and still be returned by
+    /// `get_updates_since`. Pause writers for an exact cut, or make applying
+    /// a change idempotent.
+    pub fn flush_with_sequence(&self, flushopts: &FlushOptions) -> Result<u64, Error> {
+        let sequence = self.latest_sequence_number();
+        self.flush_opt(flushopts)?;
+        Ok(sequence)
+    }
+
+    /// Flush a column family's memtable to SST files on disk (with options).
+    pub fn flush_cf_opt(&self, cf: &ColumnFamily, flushopts: &FlushOptions) -> Result<(), Error> {
         unsafe {
-            ffi_try!(ffi::rocksdb_write(self.inner, writeopts.inner, batch.inner,));
+            ffi_try!(ffi::rocksdb_flush_cf(self.inner, flushopts.inner, cf.inner,));
         }
         Ok(())
     }
 
-    pub fn write(&self, batch: WriteBatch) -> Result<(), Error> {
-        self.write_opt(batch, &WriteOptions::default())
+    /// Flush a column family's memtable to SST files on disk.
+    pub fn flush_cf(&self, cf: &ColumnFamily) -> Result<(), Error> {
+        self.flush_cf_opt(cf, &FlushOptions::default())
     }
 
-    pub fn write_without_wal(&self, batch: WriteBatch) -> Result<(), Error> {
-        let mut wo = WriteOptions::new();
-        wo.disable_wal(true);
-        self.write_opt(batch, &wo)
+    /// Flush the memtables of the default and every other open column family
+    /// to SST files on disk (with options).
+    ///
+    /// Column families are flushed one after the other, not atomically.
+    pub fn flush_all_opt(&self, flushopts: &FlushOptions) -> Result<(), Error> {
+        self.flush_opt(flushopts)?;
+        for (name, cf) in &self.cfs {
+            if name != "default" {
+                self.flush_cf_opt(cf, flushopts)?;
+            }
+        }
+        Ok(())
     }
 
-    pub fn get_opt<K: AsRef<[u8]>>(
-        &self,
-        key: K,
-        readopts: &ReadOptions,
-    ) -> Result<Option<DBVector>, Error> {
-        if readopts.inner.is_null() {
-            return Err(Error::new(
-                "Unable to create RocksDB read options. \
-                 This is a fairly trivial call, and its \
-                 failure may be indicative of a \
-                 mis-compiled or mis-loaded RocksDB \
-                 library."
-                    .to_owned(),
-            ));
-        }
+    /// Flush the memtables of the default and every other open column family
+    /// to SST files on disk.
+    pub fn flush_all(&self) -> Result<(), Error> {
+        self.flush_all_opt(&FlushOptions::default())
+    }
 
-        let key = key.as_ref();
+    /// Close the database, reporting any error encountered while shutting down.
+    ///
+    /// All column families are flushed and background work is stopped before
+    /// the database is closed, so that writes which were only held in memory
+    /// (e.g. written without the WAL) are persisted or the failure is reported.
+    /// Dropping a `DB` does the same cleanup but has to ignore errors.
+    ///
+    /// Since this takes `self` by value, any column family handles, snapshots
+    /// and iterators borrowed from the database must be dropped first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{DB, Options};
+    ///
+    /// let path = "_path_for_rocksdb_storage_close";
+    /// {
+    ///     let db = DB::open_default(path).unwrap();
+    ///     db.put(b"my key", b"my value").unwrap();
+    ///     db.close().unwrap();
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn close(self) -> Result<(), Error> {
+        self.flush_all()?;
+        unsafe {
+            ffi::rocksdb_cancel_all_background_work(self.inner, true as c_uchar);
+        }
+        Ok(())
+    }
+
+    /// Atomically applies the updates of a `WriteBatch`.
+    ///
+    /// With `Options::set_value_checksum`, batches that put values must be
+    /// created with `WriteBatch::with_value_checksum`, and are rejected with
+    /// an error otherwise. Batches of deletes can be written either way.
+    pub fn write_opt(&self, batch: WriteBatch, writeopts: &WriteOptions) -> Result<(), Error> {
+        self.check_batch_allowed(batch.has_values, batch.value_checksum)?;
+        self.write_observed(
+            || unsafe {
+                ffi_try!(ffi::rocksdb_write(self.inner, writeopts.inner, batch.inner,));
+                Ok(())
+            },
+            |observer| observer(&batch),
+        )
+    }
+
+    /// Runs `write` and then `notify` with the write observer, if one is set.
+    /// While an observer is set, observed writes are serialized so that it
+    /// sees them in the order RocksDB applied them.
+    fn write_observed<W, N>(&self, write: W, notify: N) -> Result<(), Error>
+    where
+        W: FnOnce() -> Result<(), Error>,
+        N: FnOnce(&WriteObserver),
+    {
+        let observer = self.write_observer.read().unwrap().clone();
+        let observer = match observer {
+            Some(observer) => observer,
+            None => return write(),
+        };
+        let _order = self.observed_writes.lock().unwrap();
+        write()?;
+        notify(&observer);
+        Ok(())
+    }
+
+    pub fn write(&self, batch: WriteBatch) -> Result<(), Error> {
+        self.write_opt(batch, &self.write_defaults())
+    }
+
+    /// Atomically applies the updates of a `WriteBatchWithIndex`. Unlike
+    /// `write`, the batch is only borrowed, so it can still be read from or
+    /// cleared and reused afterwards.
+    pub fn write_wbwi(&self, batch: &WriteBatchWithIndex) -> Result<(), Error> {
+        self.write_wbwi_opt(batch, &self.write_defaults())
+    }
+
+    pub fn write_wbwi_opt(
+        &self,
+        batch: &WriteBatchWithIndex,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        self.check_batch_allowed(batch.has_values, batch.value_checksum)?;
+        self.write_observed(
+            || unsafe {
+                ffi_try!(ffi::rocksdb_write_writebatch_wi(
+                    self.inner,
+                    writeopts.inner,
+                    batch.inner,
+                ));
+                Ok(())
+            },
+            |observer| observer(&WriteBatch::from_data(batch.data())),
+        )
+    }
+
+    pub fn write_without_wal(&self, batch: WriteBatch) -> Result<(), Error> {
+        let mut wo = WriteOptions::new();
+        wo.disable_wal(true);
+        self.write_opt(batch, &wo)
+    }
+
+    pub fn get_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(Error::new(
+                "Unable to create RocksDB read options. \
+                 This is a fairly trivial call, and its \
+                 failure may be indicative of a \
+                 mis-compiled or mis-loaded RocksDB \
+                 library."
+                    .to_owned(),
+            ));
+        }
+
+        let key = key.as_ref();
 
         unsafe {
             let mut val_len: size_t = 0;
@@ -922,14 +1738,15 @@ impl DB {
             if val.is_null() {
                 Ok(None)
             } else {
-                Ok(Some(DBVector::from_c(val, val_len)))
+                self.strip_value_checksum(key, DBVector::from_c(val, val_len))
+                    .map(Some)
             }
         }
     }
 
     /// Return the bytes associated with a key value
     pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<DBVector>, Error> {
-        self.get_opt(key.as_ref(), &ReadOptions::default())
+        self.get_opt(key.as_ref(), &self.read_defaults())
     }
 
     pub fn get_cf_opt<K: AsRef<[u8]>>(
@@ -964,7 +1781,8 @@ impl DB {
             if val.is_null() {
                 Ok(None)
             } else {
-                Ok(Some(DBVector::from_c(val, val_len)))
+                self.strip_value_checksum(key, DBVector::from_c(val, val_len))
+                    .map(Some)
             }
         }
     }
@@ -974,7 +1792,118 @@ impl DB {
         cf: &ColumnFamily,
         key: K,
     ) -> Result<Option<DBVector>, Error> {
-        self.get_cf_opt(cf, key.as_ref(), &ReadOptions::default())
+        self.get_cf_opt(cf, key.as_ref(), &self.read_defaults())
+    }
+
+    /// Return the values associated with the given keys, in the same order as
+    /// the keys. Each key is looked up independently, so each lookup can fail
+    /// on its own.
+    ///
+    /// Like `get`, keys with pending merge operands are returned fully merged.
+    /// The merge operator runs once per such key as part of the lookup, so its
+    /// cost grows with the number of operands that have not been compacted
+    /// yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{DB, Options};
+    ///
+    /// let path = "_path_for_rocksdb_storage_multi_get";
+    /// {
+    ///     let db = DB::open_default(path).unwrap();
+    ///     db.put(b"k1", b"v1").unwrap();
+    ///     let values: Vec<_> = db
+    ///         .multi_get(&[b"k1", b"k2"])
+    ///         .into_iter()
+    ///         .map(|v| v.unwrap().map(|v| v.to_vec()))
+    ///         .collect();
+    ///     assert_eq!(values, vec![Some(b"v1".to_vec()), None]);
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<DBVector>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.multi_get_opt(keys, &self.read_defaults())
+    }
+
+    /// Return the values associated with the given keys using the given read options.
+    pub fn multi_get_opt<K, I>(
+        &self,
+        keys: I,
+        readopts: &ReadOptions,
+    ) -> Vec<Result<Option<DBVector>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let (keys_list, keys_list_sizes) = multi_get_keys(&keys);
+        let mut values = MultiGetValues::new(keys.len());
+
+        unsafe {
+            ffi::rocksdb_multi_get(
+                self.inner,
+                readopts.inner,
+                keys.len() as size_t,
+                keys_list.as_ptr(),
+                keys_list_sizes.as_ptr(),
+                values.values.as_mut_ptr(),
+                values.sizes.as_mut_ptr(),
+                values.errors.as_mut_ptr(),
+            );
+        }
+        values.into_results(self, &keys)
+    }
+
+    /// Return the values associated with the given keys in a column family.
+    pub fn multi_get_cf<K, I>(
+        &self,
+        cf: &ColumnFamily,
+        keys: I,
+    ) -> Vec<Result<Option<DBVector>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.multi_get_cf_opt(cf, keys, &self.read_defaults())
+    }
+
+    /// Return the values associated with the given keys in a column family
+    /// using the given read options.
+    pub fn multi_get_cf_opt<K, I>(
+        &self,
+        cf: &ColumnFamily,
+        keys: I,
+        readopts: &ReadOptions,
+    ) -> Vec<Result<Option<DBVector>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let (keys_list, keys_list_sizes) = multi_get_keys(&keys);
+        let cfs: Vec<*const ffi::rocksdb_column_family_handle_t> =
+            vec![cf.inner as *const _; keys.len()];
+        let mut values = MultiGetValues::new(keys.len());
+
+        unsafe {
+            ffi::rocksdb_multi_get_cf(
+                self.inner,
+                readopts.inner,
+                cfs.as_ptr(),
+                keys.len() as size_t,
+                keys_list.as_ptr(),
+                keys_list_sizes.as_ptr(),
+                values.values.as_mut_ptr(),
+                values.sizes.as_mut_ptr(),
+                values.errors.as_mut_ptr(),
+            );
+        }
+        values.into_results(self, &keys)
     }
 
     /// Return the value associated with a key using RocksDB's PinnableSlice
@@ -1006,7 +1935,8 @@ impl DB {
             if val.is_null() {
                 Ok(None)
             } else {
-                Ok(Some(DBPinnableSlice::from_c(val)))
+                self.strip_pinned_checksum(key, DBPinnableSlice::from_c(val))
+                    .map(Some)
             }
         }
     }
@@ -1015,7 +1945,7 @@ impl DB {
     /// so as to avoid unnecessary memory copy. Similar to get_pinned_opt but
     /// leverages default options.
     pub fn get_pinned<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<DBPinnableSlice>, Error> {
-        self.get_pinned_opt(key, &ReadOptions::default())
+        self.get_pinned_opt(key, &self.read_defaults())
     }
 
     /// Return the value associated with a key using RocksDB's PinnableSlice
@@ -1050,7 +1980,8 @@ impl DB {
             if val.is_null() {
                 Ok(None)
             } else {
-                Ok(Some(DBPinnableSlice::from_c(val)))
+                self.strip_pinned_checksum(key, DBPinnableSlice::from_c(val))
+                    .map(Some)
             }
         }
     }
@@ -1063,7 +1994,84 @@ impl DB {
         cf: &ColumnFamily,
         key: K,
     ) -> Result<Option<DBPinnableSlice>, Error> {
-        self.get_pinned_cf_opt(cf, key, &ReadOptions::default())
+        self.get_pinned_cf_opt(cf, key, &self.read_defaults())
+    }
+
+    /// Return the size of the value associated with a key, without copying
+    /// the value out of the database.
+    ///
+    /// The value is still read from its block, but only pinned for the
+    /// duration of this call. Like `get`, the checksum added by
+    /// `Options::set_value_checksum` is verified and not counted.
+    pub fn get_value_size<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<usize>, Error> {
+        Ok(self.get_pinned(key)?.map(|value| value.len()))
+    }
+
+    /// Return the size of the value associated with a key in a column family,
+    /// without copying the value out of the database.
+    pub fn get_value_size_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+    ) -> Result<Option<usize>, Error> {
+        Ok(self.get_pinned_cf(cf, key)?.map(|value| value.len()))
+    }
+
+    /// Returns whether a value exists for the key, without copying the value
+    /// out of the database.
+    ///
+    /// Unlike a bloom filter check, the answer is definitive: the key is
+    /// looked up like with `get`, so this has about the same cost apart from
+    /// the copy.
+    pub fn key_exists<K: AsRef<[u8]>>(&self, key: K) -> Result<bool, Error> {
+        Ok(self.get_pinned(key)?.is_some())
+    }
+
+    /// Returns whether a value exists for the key in a column family, without
+    /// copying the value out of the database.
+    pub fn key_exists_cf<K: AsRef<[u8]>>(&self, cf: &ColumnFamily, key: K) -> Result<bool, Error> {
+        Ok(self.get_pinned_cf(cf, key)?.is_some())
+    }
+
+    /// Reads the value associated with a key into `buf`, replacing its
+    /// contents, and returns the length of the value. Reusing the same buffer
+    /// across calls avoids allocating for every read once it is large enough.
+    ///
+    /// `buf` is left empty if the key is not found. Values written with
+    /// `Options::set_value_checksum` are verified like with `get`.
+    pub fn get_into<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<usize>, Error> {
+        let value = self.get_pinned(key)?;
+        self.copy_value_into(value, buf)
+    }
+
+    /// Reads the value associated with a key in a column family into `buf`,
+    /// like `get_into`.
+    pub fn get_into_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<usize>, Error> {
+        let value = self.get_pinned_cf(cf, key)?;
+        self.copy_value_into(value, buf)
+    }
+
+    fn copy_value_into(
+        &self,
+        value: Option<DBPinnableSlice>,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<usize>, Error> {
+        buf.clear();
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        buf.extend_from_slice(&value);
+        Ok(Some(value.len()))
     }
 
     pub fn create_cf<N: AsRef<str>>(&mut self, name: N, opts: &Options) -> Result<(), Error> {
@@ -1097,8 +2105,9 @@ impl DB {
             }
             Ok(())
         } else {
-            Err(Error::new(
-                format!("Invalid column family: {}", name).to_owned(),
+            Err(Error::with_kind(
+                ErrorKind::ColumnFamilyNotFound,
+                format!("Invalid column family: {}", name),
             ))
         }
     }
@@ -1108,12 +2117,133 @@ impl DB {
         self.cfs.get(name)
     }
 
-    pub fn iterator(&self, mode: IteratorMode) -> DBIterator {
-        let readopts = ReadOptions::default();
-        self.iterator_opt(mode, &readopts)
+    /// Returns `true` if the named column family is open in this database.
+    ///
+    /// Column families are opened with `open_cf` or `create_cf` and stop
+    /// existing once `drop_cf` is called on them.
+    pub fn cf_exists(&self, name: &str) -> bool {
+        self.cfs.contains_key(name)
+    }
+
+    /// Opens an iterator over the database, using the default read options.
+    pub fn iterator(&self, mode: IteratorMode) -> Result<DBIterator, Error> {
+        let readopts = self.read_defaults();
+        let mut iter = self.iterator_opt(mode, &readopts)?;
+        iter.raw.readopts = Some(readopts);
+        Ok(iter)
+    }
+
+    /// Limits the number of iterators, of any kind and on any column family,
+    /// that can be open on this database at the same time. Opening another
+    /// one fails with an error until one of the open iterators is dropped. This is
+    /// meant to catch leaked iterators early, e.g. in tests. Iterators that
+    /// are already open when the limit is lowered are not affected.
+    ///
+    /// Default: `0` (unlimited)
+    pub fn set_max_open_iterators(&self, max: usize) {
+        self.open_iterators.max.store(max, Ordering::SeqCst);
+    }
+
+    /// Sets the read options used by the methods that don't take any, e.g.
+    /// `get`, `get_pinned`, `multi_get`, `iterator` and `raw_iterator`, as
+    /// well as their `_cf` variants.
+    ///
+    /// The following methods set up their own read options and start from
+    /// `ReadOptions::default()` instead: `full_iterator`, `prefix_iterator`,
+    /// `prefix_iterator_capped`, `warm_cache`, `consistent_scan`, `scan` and
+    /// `truncate_cf` (along with their `_cf` variants), the `get` and iterator
+    /// methods of a `Snapshot`, and `WriteBatchWithIndex::get_from_batch_and_db`.
+    ///
+    /// This can be called at any time, also on a database shared with
+    /// `DB::open_shared`. Reads that are already running, and iterators
+    /// that are already open, keep using the previous options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{Options, ReadOptions, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_default_read_options";
+    /// {
+    ///     let db = DB::open_default(path).unwrap();
+    ///     let mut readopts = ReadOptions::default();
+    ///     readopts.fill_cache(false);
+    ///     db.set_default_read_options(readopts);
+    ///     assert!(db.get(b"k1").unwrap().is_none());
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn set_default_read_options(&self, readopts: ReadOptions) {
+        *self.default_read_opts.write().unwrap() = Arc::new(readopts);
+    }
+
+    /// Sets the write options used by the methods that don't take any, e.g.
+    /// `put`, `merge`, `delete`, `delete_batch`, `write`, `write_wbwi` and
+    /// `truncate_cf`, as well as their `_cf` variants. `write_without_wal`
+    /// starts from `WriteOptions::default()` instead.
+    ///
+    /// Like `set_default_read_options`, this can be called at any time;
+    /// writes that are already running keep using the previous options.
+    pub fn set_default_write_options(&self, writeopts: WriteOptions) {
+        *self.default_write_opts.write().unwrap() = Arc::new(writeopts);
+    }
+
+    fn read_defaults(&self) -> Arc<ReadOptions> {
+        self.default_read_opts.read().unwrap().clone()
+    }
+
+    fn write_defaults(&self) -> Arc<WriteOptions> {
+        self.default_write_opts.read().unwrap().clone()
+    }
+
+    /// Sets a function that is called with every successful update of the
+    /// database, e.g. to keep an audit log. Batches written with `write`,
+    /// `write_opt`, `write_without_wal` and `write_wbwi` are passed as is
+    /// (the latter as a copy), and single-key updates like `put`, `merge`
+    /// and `delete`, including their `_cf` variants, are still applied on
+    /// their own but passed to the observer as a batch of one update. This
+    /// replaces any previous observer.
+    ///
+    /// The observer runs on the writing thread once the write has returned.
+    /// While an observer is set, these writes are serialized, so it sees them
+    /// in the order RocksDB applied them even if the database is shared
+    /// between threads. The observer must therefore not write to the database
+    /// itself. Without an observer, writes only pay for taking an uncontended
+    /// read lock.
+    ///
+    /// ```
+    /// use rocksdb::{Options, WriteBatch, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_write_observer";
+    /// {
+    ///     let db = DB::open_default(path).unwrap();
+    ///     db.set_write_observer(|batch: &WriteBatch| {
+    ///         println!("wrote {} updates", batch.len());
+    ///     });
+    ///     let mut batch = WriteBatch::default();
+    ///     batch.put(b"k1", b"v1").unwrap();
+    ///     db.write(batch).unwrap();
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn set_write_observer<F>(&self, observer: F)
+    where
+        F: Fn(&WriteBatch) + Send + Sync + 'static,
+    {
+        *self.write_observer.write().unwrap() = Some(Arc::new(observer));
+    }
+
+    /// Removes the observer set with `set_write_observer`.
+    pub fn clear_write_observer(&self) {
+        *self.write_observer.write().unwrap() = None;
     }
 
-    pub fn iterator_opt(&self, mode: IteratorMode, readopts: &ReadOptions) -> DBIterator {
+    /// Opens an iterator over the database, using the given read options.
+    pub fn iterator_opt(
+        &self,
+        mode: IteratorMode,
+        readopts: &ReadOptions,
+    ) -> Result<DBIterator, Error> {
         DBIterator::new(self, &readopts, mode)
     }
 
@@ -1131,13 +2261,15 @@ impl DB {
     /// Opens an iterator with `set_total_order_seek` enabled.
     /// This must be used to iterate across prefixes when `set_memtable_factory` has been called
     /// with a Hash-based implementation.
-    pub fn full_iterator(&self, mode: IteratorMode) -> DBIterator {
+    pub fn full_iterator(&self, mode: IteratorMode) -> Result<DBIterator, Error> {
         let mut opts = ReadOptions::default();
         opts.set_total_order_seek(true);
         DBIterator::new(self, &opts, mode)
     }
 
-    pub fn prefix_iterator<P: AsRef<[u8]>>(&self, prefix: P) -> DBIterator {
+    /// Opens an iterator over the keys starting with `prefix`, using
+    /// `set_prefix_same_as_start`.
+    pub fn prefix_iterator<P: AsRef<[u8]>>(&self, prefix: P) -> Result<DBIterator, Error> {
         let mut opts = ReadOptions::default();
         opts.set_prefix_same_as_start(true);
         DBIterator::new(
@@ -1152,10 +2284,17 @@ impl DB {
         cf_handle: &ColumnFamily,
         mode: IteratorMode,
     ) -> Result<DBIterator, Error> {
-        let opts = ReadOptions::default();
-        DBIterator::new_cf(self, cf_handle, &opts, mode)
+        let readopts = self.read_defaults();
+        let mut iter = DBIterator::new_cf(self, cf_handle, &readopts, mode)?;
+        iter.raw.readopts = Some(readopts);
+        Ok(iter)
     }
 
+    /// Opens an iterator over the given column family with `set_total_order_seek` enabled.
+    ///
+    /// Use this whenever every key of the column family must be visited, regardless of
+    /// the column family's prefix extractor and memtable implementation. See
+    /// [`full_iterator`](DB::full_iterator).
     pub fn full_iterator_cf(
         &self,
         cf_handle: &ColumnFamily,
@@ -1181,20 +2320,55 @@ impl DB {
         )
     }
 
+    /// Iterates over the keys starting with `prefix`, in order, but returns
+    /// at most `max_items` of them, e.g. so that a single large prefix cannot
+    /// monopolize a request. Use `is_truncated` on the iterator to find out
+    /// whether keys were left out.
+    ///
+    /// Keys are compared with the prefix directly, so this does not depend
+    /// on a prefix extractor being configured.
+    pub fn prefix_iterator_capped<P: AsRef<[u8]>>(
+        &self,
+        prefix: P,
+        max_items: usize,
+    ) -> Result<CappedPrefixIterator, Error> {
+        let mut opts = ReadOptions::default();
+        opts.set_total_order_seek(true);
+        let raw = DBRawIterator::new(self, &opts)?;
+        Ok(CappedPrefixIterator::new(raw, prefix.as_ref(), max_items))
+    }
+
+    /// Like `prefix_iterator_capped`, for the given column family.
+    pub fn prefix_iterator_capped_cf<P: AsRef<[u8]>>(
+        &self,
+        cf_handle: &ColumnFamily,
+        prefix: P,
+        max_items: usize,
+    ) -> Result<CappedPrefixIterator, Error> {
+        let mut opts = ReadOptions::default();
+        opts.set_total_order_seek(true);
+        let raw = DBRawIterator::new_cf(self, cf_handle, &opts)?;
+        Ok(CappedPrefixIterator::new(raw, prefix.as_ref(), max_items))
+    }
+
     /// Opens a raw iterator over the database, using the default read options
-    pub fn raw_iterator(&self) -> DBRawIterator {
-        let opts = ReadOptions::default();
-        DBRawIterator::new(self, &opts)
+    pub fn raw_iterator(&self) -> Result<DBRawIterator, Error> {
+        let readopts = self.read_defaults();
+        let mut iter = DBRawIterator::new(self, &readopts)?;
+        iter.readopts = Some(readopts);
+        Ok(iter)
     }
 
     /// Opens a raw iterator over the given column family, using the default read options
     pub fn raw_iterator_cf(&self, cf_handle: &ColumnFamily) -> Result<DBRawIterator, Error> {
-        let opts = ReadOptions::default();
-        DBRawIterator::new_cf(self, cf_handle, &opts)
+        let readopts = self.read_defaults();
+        let mut iter = DBRawIterator::new_cf(self, cf_handle, &readopts)?;
+        iter.readopts = Some(readopts);
+        Ok(iter)
     }
 
     /// Opens a raw iterator over the database, using the given read options
-    pub fn raw_iterator_opt(&self, readopts: &ReadOptions) -> DBRawIterator {
+    pub fn raw_iterator_opt(&self, readopts: &ReadOptions) -> Result<DBRawIterator, Error> {
         DBRawIterator::new(self, readopts)
     }
 
@@ -1207,78 +2381,421 @@ impl DB {
         DBRawIterator::new_cf(self, cf_handle, readopts)
     }
 
-    pub fn snapshot(&self) -> Snapshot {
-        Snapshot::new(self)
+    /// Load the blocks holding the keys in `[start, end)` into the block cache.
+    ///
+    /// The range is scanned with `fill_cache` enabled but nothing is copied
+    /// out of the database, which makes this a cheap way to warm up hot ranges
+    /// after opening a database. `None` leaves that side of the range unbounded.
+    pub fn warm_cache<S: AsRef<[u8]>, E: AsRef<[u8]>>(
+        &self,
+        start: Option<S>,
+        end: Option<E>,
+    ) -> Result<(), Error> {
+        self.warm_cache_impl(
+            None,
+            start.as_ref().map(AsRef::as_ref),
+            end.as_ref().map(AsRef::as_ref),
+        )
     }
 
-    pub fn put_opt<K, V>(&self, key: K, value: V, writeopts: &WriteOptions) -> Result<(), Error>
-    where
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
-        let key = key.as_ref();
-        let value = value.as_ref();
-
-        unsafe {
-            ffi_try!(ffi::rocksdb_put(
-                self.inner,
-                writeopts.inner,
-                key.as_ptr() as *const c_char,
-                key.len() as size_t,
-                value.as_ptr() as *const c_char,
-                value.len() as size_t,
-            ));
-            Ok(())
-        }
+    /// Load the blocks holding the keys in `[start, end)` of the given column
+    /// family into the block cache. See [`warm_cache`](DB::warm_cache).
+    pub fn warm_cache_cf<S: AsRef<[u8]>, E: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        start: Option<S>,
+        end: Option<E>,
+    ) -> Result<(), Error> {
+        self.warm_cache_impl(
+            Some(cf),
+            start.as_ref().map(AsRef::as_ref),
+            end.as_ref().map(AsRef::as_ref),
+        )
     }
 
-    pub fn put_cf_opt<K, V>(
+    fn warm_cache_impl(
         &self,
-        cf: &ColumnFamily,
-        key: K,
-        value: V,
-        writeopts: &WriteOptions,
-    ) -> Result<(), Error>
-    where
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
-        let key = key.as_ref();
-        let value = value.as_ref();
+        cf: Option<&ColumnFamily>,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        let mut readopts = ReadOptions::default();
+        readopts.fill_cache(true);
+        if let Some(end) = end {
+            // `end` outlives the iterator created below.
+            unsafe {
+                readopts.set_iterate_upper_bound(end);
+            }
+        }
 
-        unsafe {
-            ffi_try!(ffi::rocksdb_put_cf(
-                self.inner,
-                writeopts.inner,
-                cf.inner,
-                key.as_ptr() as *const c_char,
-                key.len() as size_t,
-                value.as_ptr() as *const c_char,
-                value.len() as size_t,
+        let mut iter = match cf {
+            Some(cf) => self.raw_iterator_cf_opt(cf, &readopts)?,
+            None => DBRawIterator::new(self, &readopts)?,
+        };
+        match start {
+            Some(start) => iter.seek(start),
+            None => iter.seek_to_first(),
+        }
+        while iter.valid() {
+            iter.next();
+        }
+        iter.status()
+    }
+
+    /// Load the filter blocks of all live SST files into the block cache, so
+    /// that the first lookups after opening don't pay for reading filters.
+    ///
+    /// This is best effort: it performs a point lookup of the smallest key of
+    /// every live file, which reads the filter of each file that lookup visits.
+    /// It only has a lasting effect when
+    /// `BlockBasedOptions::set_cache_index_and_filter_blocks` is enabled, as
+    /// filters are otherwise held by the table readers rather than the cache.
+    pub fn preload_filters(&self) -> Result<(), Error> {
+        self.preload_filters_impl(None)
+    }
+
+    /// Like [`preload_filters`](DB::preload_filters), but for the given column family.
+    pub fn preload_filters_cf(&self, cf: &ColumnFamily) -> Result<(), Error> {
+        self.preload_filters_impl(Some(cf))
+    }
+
+    fn preload_filters_impl(&self, cf: Option<&ColumnFamily>) -> Result<(), Error> {
+        // Live file metadata doesn't say which column family a file belongs
+        // to, so files of other column families just cause extra lookups.
+        let keys: Vec<Vec<u8>> = unsafe {
+            let files = ffi::rocksdb_livefiles(self.inner);
+            let keys = (0..ffi::rocksdb_livefiles_count(files))
+                .map(|i| {
+                    let mut len: size_t = 0;
+                    let key = ffi::rocksdb_livefiles_smallestkey(files, i, &mut len);
+                    slice::from_raw_parts(key as *const u8, len as usize).to_vec()
+                })
+                .collect();
+            ffi::rocksdb_livefiles_destroy(files);
+            keys
+        };
+
+        for key in keys {
+            match cf {
+                Some(cf) => self.get_pinned_cf(cf, key)?,
+                None => self.get_pinned(key)?,
+            };
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self)
+    }
+
+    /// Start building an iterator with custom bounds and read options. See
+    /// [`ScanBuilder`](ScanBuilder).
+    pub fn scan(&self) -> ScanBuilder {
+        ScanBuilder::new(self)
+    }
+
+    /// Calls `f` with every key/value pair in `[start, end)`, as of a snapshot
+    /// taken when the scan starts, until `f` returns `ControlFlow::Break`.
+    ///
+    /// Writes made while the scan is running (from this or any other thread)
+    /// are not observed. The snapshot is released when the scan returns,
+    /// including when `f` panics. Returns the value `f` broke with, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::DB;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let path = "_path_for_rocksdb_storage_consistent_scan";
+    /// {
+    ///     let db = DB::open_default(path).unwrap();
+    ///     db.put(b"k1", b"v1").unwrap();
+    ///     db.put(b"k2", b"v2").unwrap();
+    ///     let found = db
+    ///         .consistent_scan(None::<&[u8]>, None::<&[u8]>, |key, _| {
+    ///             if key == b"k2" {
+    ///                 ControlFlow::Break(key.to_vec())
+    ///             } else {
+    ///                 ControlFlow::Continue(())
+    ///             }
+    ///         })
+    ///         .unwrap();
+    ///     assert_eq!(found, Some(b"k2".to_vec()));
+    /// }
+    /// let _ = DB::destroy(&rocksdb::Options::default(), path);
+    /// ```
+    pub fn consistent_scan<S, E, T, F>(
+        &self,
+        start: Option<S>,
+        end: Option<E>,
+        f: F,
+    ) -> Result<Option<T>, Error>
+    where
+        S: AsRef<[u8]>,
+        E: AsRef<[u8]>,
+        F: FnMut(&[u8], &[u8]) -> ControlFlow<T>,
+    {
+        self.consistent_scan_impl(
+            None,
+            start.as_ref().map(AsRef::as_ref),
+            end.as_ref().map(AsRef::as_ref),
+            f,
+        )
+    }
+
+    /// Like [`consistent_scan`](DB::consistent_scan), but over the given column family.
+    pub fn consistent_scan_cf<S, E, T, F>(
+        &self,
+        cf: &ColumnFamily,
+        start: Option<S>,
+        end: Option<E>,
+        f: F,
+    ) -> Result<Option<T>, Error>
+    where
+        S: AsRef<[u8]>,
+        E: AsRef<[u8]>,
+        F: FnMut(&[u8], &[u8]) -> ControlFlow<T>,
+    {
+        self.consistent_scan_impl(
+            Some(cf),
+            start.as_ref().map(AsRef::as_ref),
+            end.as_ref().map(AsRef::as_ref),
+            f,
+        )
+    }
+
+    fn consistent_scan_impl<T, F>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        mut f: F,
+    ) -> Result<Option<T>, Error>
+    where
+        F: FnMut(&[u8], &[u8]) -> ControlFlow<T>,
+    {
+        // Declared in this order so the iterator is dropped before the read
+        // options it points into, and both before the snapshot is released.
+        let snapshot = self.snapshot();
+        let mut readopts = ReadOptions::default();
+        readopts.set_snapshot(&snapshot);
+        if let Some(end) = end {
+            unsafe {
+                readopts.set_iterate_upper_bound(end);
+            }
+        }
+
+        let mut iter = match cf {
+            Some(cf) => DBRawIterator::new_cf(self, cf, &readopts)?,
+            None => DBRawIterator::new(self, &readopts)?,
+        };
+        match start {
+            Some(start) => iter.seek(start),
+            None => iter.seek_to_first(),
+        }
+        while iter.valid() {
+            let (key, value) = unsafe { (iter.key_inner().unwrap(), iter.value_inner().unwrap()) };
+            if let ControlFlow::Break(result) = f(key, value) {
+                return Ok(Some(result));
+            }
+            iter.next();
+        }
+        iter.status().map(|_| None)
+    }
+
+    fn strip_value_checksum(&self, key: &[u8], value: DBVector) -> Result<DBVector, Error> {
+        if !self.value_checksum {
+            return Ok(value);
+        }
+        strip_checksum(key, value)
+    }
+
+    fn strip_pinned_checksum<'v>(
+        &self,
+        key: &[u8],
+        mut value: DBPinnableSlice<'v>,
+    ) -> Result<DBPinnableSlice<'v>, Error> {
+        if !self.value_checksum {
+            return Ok(value);
+        }
+        match checksum::verify(key, &value) {
+            Some(len) => {
+                value.trailer_len = value.len() - len;
+                Ok(value)
+            }
+            None => Err(checksum_mismatch()),
+        }
+    }
+
+    /// Checks that a batch whose values have checksums, as told by
+    /// `value_checksum`, is only written to a database that verifies them,
+    /// and the other way around.
+    fn check_batch_allowed(&self, has_values: bool, value_checksum: bool) -> Result<(), Error> {
+        if !has_values || value_checksum == self.value_checksum {
+            return Ok(());
+        }
+        let message = if self.value_checksum {
+            "Write batches with values must be created with `with_value_checksum` \
+             when value checksums are enabled"
+        } else {
+            "Write batches created with `with_value_checksum` can only be written \
+             with value checksums enabled"
+        };
+        Err(Error::new(message.to_owned()))
+    }
+
+    fn check_merge_allowed(&self) -> Result<(), Error> {
+        if self.value_checksum {
+            return Err(merge_with_checksum());
+        }
+        Ok(())
+    }
+
+    /// Runs `write` for `key` if its prefix is not lower than the last one
+    /// written to `cf`, and records the prefix once the write succeeded. The
+    /// lock is held during the write so that concurrent puts are checked in
+    /// the order they are applied.
+    fn with_monotonic_prefix<F>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: &[u8],
+        write: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce() -> Result<(), Error>,
+    {
+        if self.monotonic_prefix_len == 0 {
+            return write();
+        }
+        let prefix = &key[..cmp::min(key.len(), self.monotonic_prefix_len)];
+        // `None` and the handle of the default column family are the same
+        // column family, so entries are keyed by name.
+        let cf_name = match cf {
+            Some(cf) => self
+                .cfs
+                .iter()
+                .find(|&(_, handle)| handle.inner == cf.inner)
+                .map_or("", |(name, _)| name.as_str()),
+            None => "default",
+        };
+        let mut last_prefixes = self.last_prefixes.lock().unwrap();
+        if let Some(last) = last_prefixes.get(cf_name) {
+            if prefix < last.as_slice() {
+                return Err(Error::new(format!(
+                    "Key prefix {:?} is lower than the last written prefix {:?}",
+                    prefix, last
+                )));
+            }
+        }
+        write()?;
+        if last_prefixes.get(cf_name).map(Vec::as_slice) != Some(prefix) {
+            last_prefixes.insert(cf_name.to_owned(), prefix.to_vec());
+        }
+        Ok(())
+    }
+
+    fn check_value_size(&self, value: &[u8]) -> Result<(), Error> {
+        if self.max_value_size > 0 && value.len() > self.max_value_size {
+            return Err(Error::with_kind(
+                ErrorKind::ValueTooLarge,
+                format!(
+                    "Value of {} bytes exceeds the maximum value size of {} bytes",
+                    value.len(),
+                    self.max_value_size
+                ),
             ));
-            Ok(())
         }
+        Ok(())
     }
 
-    pub fn merge_opt<K, V>(&self, key: K, value: V, writeopts: &WriteOptions) -> Result<(), Error>
+    pub fn put_opt<K, V>(&self, key: K, value: V, writeopts: &WriteOptions) -> Result<(), Error>
     where
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
         let key = key.as_ref();
         let value = value.as_ref();
+        self.check_value_size(value)?;
+        let value = with_checksum(self.value_checksum, key, value);
+
+        self.with_monotonic_prefix(None, key, || {
+            self.write_observed(
+                || unsafe {
+                    ffi_try!(ffi::rocksdb_put(
+                        self.inner,
+                        writeopts.inner,
+                        key.as_ptr() as *const c_char,
+                        key.len() as size_t,
+                        value.as_ptr() as *const c_char,
+                        value.len() as size_t,
+                    ));
+                    Ok(())
+                },
+                |observer| notify_one(observer, |batch| batch.put(key, &*value)),
+            )
+        })
+    }
 
-        unsafe {
-            ffi_try!(ffi::rocksdb_merge(
-                self.inner,
-                writeopts.inner,
-                key.as_ptr() as *const c_char,
-                key.len() as size_t,
-                value.as_ptr() as *const c_char,
-                value.len() as size_t,
-            ));
-            Ok(())
-        }
+    pub fn put_cf_opt<K, V>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        value: V,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+        self.check_value_size(value)?;
+        let value = with_checksum(self.value_checksum, key, value);
+
+        self.with_monotonic_prefix(Some(cf), key, || {
+            self.write_observed(
+                || unsafe {
+                    ffi_try!(ffi::rocksdb_put_cf(
+                        self.inner,
+                        writeopts.inner,
+                        cf.inner,
+                        key.as_ptr() as *const c_char,
+                        key.len() as size_t,
+                        value.as_ptr() as *const c_char,
+                        value.len() as size_t,
+                    ));
+                    Ok(())
+                },
+                |observer| notify_one(observer, |batch| batch.put_cf(cf, key, &*value)),
+            )
+        })
+    }
+
+    pub fn merge_opt<K, V>(&self, key: K, value: V, writeopts: &WriteOptions) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+        self.check_value_size(value)?;
+        self.check_merge_allowed()?;
+
+        self.write_observed(
+            || unsafe {
+                ffi_try!(ffi::rocksdb_merge(
+                    self.inner,
+                    writeopts.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    value.as_ptr() as *const c_char,
+                    value.len() as size_t,
+                ));
+                Ok(())
+            },
+            |observer| notify_one(observer, |batch| batch.merge(key, value)),
+        )
     }
 
     pub fn merge_cf_opt<K, V>(
@@ -1294,19 +2811,24 @@ impl DB {
     {
         let key = key.as_ref();
         let value = value.as_ref();
-
-        unsafe {
-            ffi_try!(ffi::rocksdb_merge_cf(
-                self.inner,
-                writeopts.inner,
-                cf.inner,
-                key.as_ptr() as *const c_char,
-                key.len() as size_t,
-                value.as_ptr() as *const c_char,
-                value.len() as size_t,
-            ));
-            Ok(())
-        }
+        self.check_value_size(value)?;
+        self.check_merge_allowed()?;
+
+        self.write_observed(
+            || unsafe {
+                ffi_try!(ffi::rocksdb_merge_cf(
+                    self.inner,
+                    writeopts.inner,
+                    cf.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    value.as_ptr() as *const c_char,
+                    value.len() as size_t,
+                ));
+                Ok(())
+            },
+            |observer| notify_one(observer, |batch| batch.merge_cf(cf, key, value)),
+        )
     }
 
     pub fn delete_opt<K: AsRef<[u8]>>(
@@ -1315,16 +2837,18 @@ impl DB {
         writeopts: &WriteOptions,
     ) -> Result<(), Error> {
         let key = key.as_ref();
-
-        unsafe {
-            ffi_try!(ffi::rocksdb_delete(
-                self.inner,
-                writeopts.inner,
-                key.as_ptr() as *const c_char,
-                key.len() as size_t,
-            ));
-            Ok(())
-        }
+        self.write_observed(
+            || unsafe {
+                ffi_try!(ffi::rocksdb_delete(
+                    self.inner,
+                    writeopts.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                ));
+                Ok(())
+            },
+            |observer| notify_one(observer, |batch| batch.delete(key)),
+        )
     }
 
     pub fn delete_cf_opt<K: AsRef<[u8]>>(
@@ -1334,17 +2858,19 @@ impl DB {
         writeopts: &WriteOptions,
     ) -> Result<(), Error> {
         let key = key.as_ref();
-
-        unsafe {
-            ffi_try!(ffi::rocksdb_delete_cf(
-                self.inner,
-                writeopts.inner,
-                cf.inner,
-                key.as_ptr() as *const c_char,
-                key.len() as size_t,
-            ));
-            Ok(())
-        }
+        self.write_observed(
+            || unsafe {
+                ffi_try!(ffi::rocksdb_delete_cf(
+                    self.inner,
+                    writeopts.inner,
+                    cf.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                ));
+                Ok(())
+            },
+            |observer| notify_one(observer, |batch| batch.delete_cf(cf, key)),
+        )
     }
 
     pub fn put<K, V>(&self, key: K, value: V) -> Result<(), Error>
@@ -1352,7 +2878,7 @@ impl DB {
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
-        self.put_opt(key.as_ref(), value.as_ref(), &WriteOptions::default())
+        self.put_opt(key.as_ref(), value.as_ref(), &self.write_defaults())
     }
 
     pub fn put_cf<K, V>(&self, cf: &ColumnFamily, key: K, value: V) -> Result<(), Error>
@@ -1360,7 +2886,7 @@ impl DB {
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
-        self.put_cf_opt(cf, key.as_ref(), value.as_ref(), &WriteOptions::default())
+        self.put_cf_opt(cf, key.as_ref(), value.as_ref(), &self.write_defaults())
     }
 
     pub fn merge<K, V>(&self, key: K, value: V) -> Result<(), Error>
@@ -1368,7 +2894,7 @@ impl DB {
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
-        self.merge_opt(key.as_ref(), value.as_ref(), &WriteOptions::default())
+        self.merge_opt(key.as_ref(), value.as_ref(), &self.write_defaults())
     }
 
     pub fn merge_cf<K, V>(&self, cf: &ColumnFamily, key: K, value: V) -> Result<(), Error>
@@ -1376,15 +2902,88 @@ impl DB {
         K: AsRef<[u8]>,
         V: AsRef<[u8]>,
     {
-        self.merge_cf_opt(cf, key.as_ref(), value.as_ref(), &WriteOptions::default())
+        self.merge_cf_opt(cf, key.as_ref(), value.as_ref(), &self.write_defaults())
+    }
+
+    /// Add `delta` to the 64-bit counter stored under `key`, creating it if
+    /// it doesn't exist yet.
+    ///
+    /// This issues a merge, so the column family must have been opened with
+    /// `Options::set_uint64add_merge_operator`; without any merge operator
+    /// RocksDB rejects the write with a "not supported" error.
+    pub fn upsert<K: AsRef<[u8]>>(&self, key: K, delta: u64) -> Result<(), Error> {
+        self.merge(key, delta.to_le_bytes())
+    }
+
+    /// Add `delta` to the 64-bit counter stored under `key` in a column
+    /// family. See [`upsert`](DB::upsert).
+    pub fn upsert_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        delta: u64,
+    ) -> Result<(), Error> {
+        self.merge_cf(cf, key, delta.to_le_bytes())
     }
 
     pub fn delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
-        self.delete_opt(key.as_ref(), &WriteOptions::default())
+        self.delete_opt(key.as_ref(), &self.write_defaults())
     }
 
     pub fn delete_cf<K: AsRef<[u8]>>(&self, cf: &ColumnFamily, key: K) -> Result<(), Error> {
-        self.delete_cf_opt(cf, key.as_ref(), &WriteOptions::default())
+        self.delete_cf_opt(cf, key.as_ref(), &self.write_defaults())
+    }
+
+    /// Atomically delete all of the given keys, using a single `WriteBatch`
+    /// (with options).
+    pub fn delete_batch_opt<K, I>(&self, keys: I, writeopts: &WriteOptions) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            batch.delete(key)?;
+        }
+        self.write_opt(batch, writeopts)
+    }
+
+    /// Atomically delete all of the given keys from a column family, using a
+    /// single `WriteBatch` (with options).
+    pub fn delete_batch_cf_opt<K, I>(
+        &self,
+        cf: &ColumnFamily,
+        keys: I,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            batch.delete_cf(cf, key)?;
+        }
+        self.write_opt(batch, writeopts)
+    }
+
+    /// Atomically delete all of the given keys, using a single `WriteBatch`.
+    pub fn delete_batch<K, I>(&self, keys: I) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.delete_batch_opt(keys, &self.write_defaults())
+    }
+
+    /// Atomically delete all of the given keys from a column family, using a
+    /// single `WriteBatch`.
+    pub fn delete_batch_cf<K, I>(&self, cf: &ColumnFamily, keys: I) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.delete_batch_cf_opt(cf, keys, &self.write_defaults())
     }
 
     pub fn compact_range<S: AsRef<[u8]>, E: AsRef<[u8]>>(&self, start: Option<S>, end: Option<E>) {
@@ -1394,198 +2993,955 @@ impl DB {
 
             ffi::rocksdb_compact_range(
                 self.inner,
-                opt_bytes_to_ptr(start),
-                start.map_or(0, |s| s.len()) as size_t,
-                opt_bytes_to_ptr(end),
-                end.map_or(0, |e| e.len()) as size_t,
+                opt_bytes_to_ptr(start),
+                start.map_or(0, |s| s.len()) as size_t,
+                opt_bytes_to_ptr(end),
+                end.map_or(0, |e| e.len()) as size_t,
+            );
+        }
+    }
+
+    pub fn compact_range_cf<S: AsRef<[u8]>, E: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        start: Option<S>,
+        end: Option<E>,
+    ) {
+        unsafe {
+            let start = start.as_ref().map(|s| s.as_ref());
+            let end = end.as_ref().map(|e| e.as_ref());
+
+            ffi::rocksdb_compact_range_cf(
+                self.inner,
+                cf.inner,
+                opt_bytes_to_ptr(start),
+                start.map_or(0, |s| s.len()) as size_t,
+                opt_bytes_to_ptr(end),
+                end.map_or(0, |e| e.len()) as size_t,
+            );
+        }
+    }
+
+    /// Deletes every key of a column family, keeping the column family itself
+    /// (and so its handle and options) intact, and reclaims the disk space.
+    ///
+    /// The keys between the first and last key are removed with a range
+    /// tombstone, after dropping the SST files entirely within that range,
+    /// and the range is then compacted. This works with any comparator.
+    /// Keys written while this runs may or may not survive, and snapshots
+    /// taken before the call no longer see the data of the dropped files.
+    pub fn truncate_cf(&self, cf: &ColumnFamily) -> Result<(), Error> {
+        let mut readopts = ReadOptions::default();
+        readopts.set_total_order_seek(true);
+        let (first, last) = {
+            let mut iter = DBRawIterator::new_cf(self, cf, &readopts)?;
+            iter.seek_to_first();
+            let first = iter.key();
+            iter.seek_to_last();
+            let last = iter.key();
+            iter.status()?;
+            match (first, last) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return Ok(()),
+            }
+        };
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_delete_file_in_range_cf(
+                self.inner,
+                cf.inner,
+                first.as_ptr() as *const c_char,
+                first.len() as size_t,
+                last.as_ptr() as *const c_char,
+                last.len() as size_t,
+            ));
+        }
+
+        // The end of a range tombstone is exclusive, so the last key is
+        // deleted on its own.
+        let mut batch = WriteBatch::default();
+        batch.delete_range_cf(cf, &first[..], &last[..])?;
+        batch.delete_cf(cf, &last)?;
+        self.write(batch)?;
+
+        self.compact_range_cf(cf, Some(&first), Some(&last));
+        Ok(())
+    }
+
+    /// Same as `compact_range`, but configured by the given `CompactOptions`.
+    pub fn compact_range_opt<S: AsRef<[u8]>, E: AsRef<[u8]>>(
+        &self,
+        start: Option<S>,
+        end: Option<E>,
+        opts: &CompactOptions,
+    ) {
+        unsafe {
+            let start = start.as_ref().map(|s| s.as_ref());
+            let end = end.as_ref().map(|e| e.as_ref());
+
+            ffi::rocksdb_compact_range_opt(
+                self.inner,
+                opts.inner,
+                opt_bytes_to_ptr(start),
+                start.map_or(0, |s| s.len()) as size_t,
+                opt_bytes_to_ptr(end),
+                end.map_or(0, |e| e.len()) as size_t,
+            );
+        }
+    }
+
+    /// Same as `compact_range_cf`, but configured by the given `CompactOptions`.
+    pub fn compact_range_cf_opt<S: AsRef<[u8]>, E: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        start: Option<S>,
+        end: Option<E>,
+        opts: &CompactOptions,
+    ) {
+        unsafe {
+            let start = start.as_ref().map(|s| s.as_ref());
+            let end = end.as_ref().map(|e| e.as_ref());
+
+            ffi::rocksdb_compact_range_cf_opt(
+                self.inner,
+                cf.inner,
+                opts.inner,
+                opt_bytes_to_ptr(start),
+                start.map_or(0, |s| s.len()) as size_t,
+                opt_bytes_to_ptr(end),
+                end.map_or(0, |e| e.len()) as size_t,
+            );
+        }
+    }
+
+    /// Compacts all the data of a column family that lives in levels
+    /// `from_level` to `to_level`, and moves the result to `to_level`.
+    ///
+    /// A manual compaction always starts at the first non-empty level, so
+    /// an error is returned if any level above `from_level` still holds
+    /// files. The bottommost level is always rewritten. Data already in
+    /// levels below `to_level` is compacted as well and moved up, so
+    /// `to_level` should normally be at least the current bottommost level.
+    pub fn compact_levels(
+        &self,
+        cf: &ColumnFamily,
+        from_level: c_int,
+        to_level: c_int,
+    ) -> Result<(), Error> {
+        if from_level < 0 || from_level > to_level {
+            return Err(Error::new(format!(
+                "Invalid level range {}..{}",
+                from_level, to_level
+            )));
+        }
+        for level in 0..from_level {
+            let property = format!("rocksdb.num-files-at-level{}", level);
+            if self.required_int_property(Some(cf), &property)? > 0 {
+                return Err(Error::new(format!(
+                    "Level {} above level {} is not empty",
+                    level, from_level
+                )));
+            }
+        }
+
+        let mut opts = CompactOptions::default();
+        opts.set_change_level(true);
+        opts.set_target_level(to_level);
+        opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+        self.compact_range_cf_opt(cf, None::<&[u8]>, None::<&[u8]>, &opts);
+        Ok(())
+    }
+
+    /// Adds SST files created with `SstFileWriter` to the database, using
+    /// the default ingestion options.
+    pub fn ingest_external_file<P: AsRef<Path>>(&self, paths: Vec<P>) -> Result<(), Error> {
+        self.ingest_external_file_opt(&IngestExternalFileOptions::default(), paths)
+    }
+
+    pub fn ingest_external_file_opt<P: AsRef<Path>>(
+        &self,
+        opts: &IngestExternalFileOptions,
+        paths: Vec<P>,
+    ) -> Result<(), Error> {
+        self.ingest_external_file_impl(None, opts, paths)
+    }
+
+    pub fn ingest_external_file_cf<P: AsRef<Path>>(
+        &self,
+        cf: &ColumnFamily,
+        paths: Vec<P>,
+    ) -> Result<(), Error> {
+        self.ingest_external_file_cf_opt(cf, &IngestExternalFileOptions::default(), paths)
+    }
+
+    pub fn ingest_external_file_cf_opt<P: AsRef<Path>>(
+        &self,
+        cf: &ColumnFamily,
+        opts: &IngestExternalFileOptions,
+        paths: Vec<P>,
+    ) -> Result<(), Error> {
+        self.ingest_external_file_impl(Some(cf), opts, paths)
+    }
+
+    fn ingest_external_file_impl<P: AsRef<Path>>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        opts: &IngestExternalFileOptions,
+        paths: Vec<P>,
+    ) -> Result<(), Error> {
+        let mut cpaths = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let path = path.as_ref();
+            match CString::new(path.to_string_lossy().as_bytes()) {
+                Ok(c) => cpaths.push(c),
+                Err(_) => {
+                    return Err(Error::new(format!(
+                        "Failed to convert path {} to CString when ingesting files.",
+                        path.display()
+                    )));
+                }
+            }
+        }
+        let cpaths: Vec<*const c_char> = cpaths.iter().map(|p| p.as_ptr()).collect();
+
+        unsafe {
+            match cf {
+                Some(cf) => ffi_try!(ffi::rocksdb_ingest_external_file_cf(
+                    self.inner,
+                    cf.inner,
+                    cpaths.as_ptr(),
+                    cpaths.len() as size_t,
+                    opts.inner,
+                )),
+                None => ffi_try!(ffi::rocksdb_ingest_external_file(
+                    self.inner,
+                    cpaths.as_ptr(),
+                    cpaths.len() as size_t,
+                    opts.inner,
+                )),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_options(&self, opts: &[(&str, &str)]) -> Result<(), Error> {
+        let copts = opts
+            .iter()
+            .map(|(name, value)| {
+                let cname = match CString::new(name.as_bytes()) {
+                    Ok(cname) => cname,
+                    Err(e) => return Err(Error::new(format!("Invalid option name `{}`", e))),
+                };
+                let cvalue = match CString::new(value.as_bytes()) {
+                    Ok(cvalue) => cvalue,
+                    Err(e) => return Err(Error::new(format!("Invalid option value: `{}`", e))),
+                };
+                Ok((cname, cvalue))
+            })
+            .collect::<Result<Vec<(CString, CString)>, Error>>()?;
+
+        let cnames: Vec<*const c_char> = copts.iter().map(|opt| opt.0.as_ptr()).collect();
+        let cvalues: Vec<*const c_char> = copts.iter().map(|opt| opt.1.as_ptr()).collect();
+        let count = opts.len() as i32;
+        unsafe {
+            ffi_try!(ffi::rocksdb_set_options(
+                self.inner,
+                count,
+                cnames.as_ptr(),
+                cvalues.as_ptr(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Retrieves a RocksDB property by name.
+    ///
+    /// For a full list of properties, see
+    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L428-L634
+    pub fn property_value(&self, name: &str) -> Result<Option<String>, Error> {
+        let prop_name = match CString::new(name) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(Error::new(format!(
+                    "Failed to convert property name to CString: {}",
+                    e
+                )));
+            }
+        };
+
+        unsafe {
+            let value = ffi::rocksdb_property_value(self.inner, prop_name.as_ptr());
+            if value.is_null() {
+                return Ok(None);
+            }
+
+            let str_value = match CStr::from_ptr(value).to_str() {
+                Ok(s) => s.to_owned(),
+                Err(e) => {
+                    return Err(Error::new(format!(
+                        "Failed to convert property value to string: {}",
+                        e
+                    )));
+                }
+            };
+
+            libc::free(value as *mut c_void);
+            Ok(Some(str_value))
+        }
+    }
+
+    /// Retrieves a RocksDB property by name, for a specific column family.
+    ///
+    /// For a full list of properties, see
+    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L428-L634
+    pub fn property_value_cf(
+        &self,
+        cf: &ColumnFamily,
+        name: &str,
+    ) -> Result<Option<String>, Error> {
+        let prop_name = match CString::new(name) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(Error::new(format!(
+                    "Failed to convert property name to CString: {}",
+                    e
+                )));
+            }
+        };
+
+        unsafe {
+            let value = ffi::rocksdb_property_value_cf(self.inner, cf.inner, prop_name.as_ptr());
+            if value.is_null() {
+                return Ok(None);
+            }
+
+            let str_value = match CStr::from_ptr(value).to_str() {
+                Ok(s) => s.to_owned(),
+                Err(e) => {
+                    return Err(Error::new(format!(
+                        "Failed to convert property value to string: {}",
+                        e
+                    )));
+                }
+            };
+
+            libc::free(value as *mut c_void);
+            Ok(Some(str_value))
+        }
+    }
+
+    /// Retrieves a RocksDB property and casts it to an integer.
+    ///
+    /// For a full list of properties that return int values, see
+    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L654-L689
+    pub fn property_int_value(&self, name: &str) -> Result<Option<u64>, Error> {
+        match self.property_value(name) {
+            Ok(Some(value)) => match value.parse::<u64>() {
+                Ok(int_value) => Ok(Some(int_value)),
+                Err(e) => Err(Error::new(format!(
+                    "Failed to convert property value to int: {}",
+                    e
+                ))),
+            },
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Retrieves a RocksDB property for a specific column family and casts it to an integer.
+    ///
+    /// For a full list of properties that return int values, see
+    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L654-L689
+    pub fn property_int_value_cf(
+        &self,
+        cf: &ColumnFamily,
+        name: &str,
+    ) -> Result<Option<u64>, Error> {
+        match self.property_value_cf(cf, name) {
+            Ok(Some(value)) => match value.parse::<u64>() {
+                Ok(int_value) => Ok(Some(int_value)),
+                Err(e) => Err(Error::new(format!(
+                    "Failed to convert property value to int: {}",
+                    e
+                ))),
+            },
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The number of currently running compactions.
+    pub fn running_compactions(&self) -> Result<u64, Error> {
+        self.required_int_property(None, "rocksdb.num-running-compactions")
+    }
+
+    /// The number of currently running flushes.
+    pub fn running_flushes(&self) -> Result<u64, Error> {
+        self.required_int_property(None, "rocksdb.num-running-flushes")
+    }
+
+    /// The estimated total number of bytes compaction needs to rewrite to get
+    /// all levels of the default column family down to their target size.
+    pub fn pending_compaction_bytes(&self) -> Result<u64, Error> {
+        self.required_int_property(None, "rocksdb.estimate-pending-compaction-bytes")
+    }
+
+    /// The number of immutable memtables of the given column family that have
+    /// not yet been flushed.
+    pub fn num_immutable_memtables(&self, cf: &ColumnFamily) -> Result<u64, Error> {
+        self.required_int_property(Some(cf), "rocksdb.num-immutable-mem-table")
+    }
+
+    /// Collects the properties most useful to look at when something goes
+    /// wrong into one `OperationalSnapshot`.
+    pub fn operational_snapshot(&self) -> Result<OperationalSnapshot, Error> {
+        let mut files_per_level = Vec::new();
+        // RocksDB rejects the property for levels beyond `num_levels`.
+        while let Some(files) = self.property_int_value(&format!(
+            "rocksdb.num-files-at-level{}",
+            files_per_level.len()
+        ))? {
+            files_per_level.push(files);
+        }
+
+        Ok(OperationalSnapshot {
+            estimate_num_keys: self.required_int_property(None, "rocksdb.estimate-num-keys")?,
+            live_sst_size: self.required_int_property(None, "rocksdb.live-sst-files-size")?,
+            pending_compaction_bytes: self.pending_compaction_bytes()?,
+            running_compactions: self.running_compactions()?,
+            running_flushes: self.running_flushes()?,
+            files_per_level,
+            block_cache_usage: self.property_int_value("rocksdb.block-cache-usage")?,
+            background_errors: self.required_int_property(None, "rocksdb.background-errors")?,
+        })
+    }
+
+    /// Collects the statistics tickers and histograms and the most important
+    /// properties of the database as a list of metrics.
+    ///
+    /// `opts` must be the options the database was opened with, since they
+    /// hold the statistics; tickers and histograms are only included if
+    /// `Options::enable_statistics` was called on them. Column family
+    /// properties are labelled with `cf`.
+    ///
+    /// ```
+    /// use rocksdb::{Options, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_collect_metrics";
+    /// {
+    ///     let mut opts = Options::default();
+    ///     opts.create_if_missing(true);
+    ///     opts.enable_statistics();
+    ///     let db = DB::open(&opts, path).unwrap();
+    ///     for metric in db.collect_metrics(&opts).unwrap() {
+    ///         println!("{} {:?} {}", metric.name, metric.labels, metric.value);
+    ///     }
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn collect_metrics(&self, opts: &Options) -> Result<Vec<Metric>, Error> {
+        let mut collected = match opts.get_statistics() {
+            Some(stats) => metrics::parse_statistics(&stats),
+            None => Vec::new(),
+        };
+
+        let mut cfs: Vec<(&str, Option<&ColumnFamily>)> = vec![("default", None)];
+        cfs.extend(
+            self.cfs
+                .iter()
+                .filter(|&(name, _)| name != "default")
+                .map(|(name, cf)| (name.as_str(), Some(cf))),
+        );
+        for &(cf_name, cf) in &cfs {
+            for name in metrics::CF_PROPERTIES {
+                let value = match cf {
+                    Some(cf) => self.property_int_value_cf(cf, name)?,
+                    None => self.property_int_value(name)?,
+                };
+                if let Some(value) = value {
+                    collected.push(metrics::property_gauge(name, value, Some(cf_name)));
+                }
+            }
+        }
+        for name in metrics::DB_PROPERTIES {
+            if let Some(value) = self.property_int_value(name)? {
+                collected.push(metrics::property_gauge(name, value, None));
+            }
+        }
+        Ok(collected)
+    }
+
+    fn required_int_property(&self, cf: Option<&ColumnFamily>, name: &str) -> Result<u64, Error> {
+        let value = match cf {
+            Some(cf) => self.property_int_value_cf(cf, name)?,
+            None => self.property_int_value(name)?,
+        };
+        value.ok_or_else(|| Error::new(format!("Property {} is not available", name)))
+    }
+
+    /// Flush all column families and block until no flushes or compactions
+    /// are running or pending.
+    ///
+    /// Once this returns, level 0 holds fewer files than the compaction trigger
+    /// (otherwise a compaction would still be pending), which makes it a
+    /// convenient deterministic starting point for benchmarks. Writes issued
+    /// concurrently may keep this from returning.
+    pub fn settle(&self) -> Result<(), Error> {
+        self.flush_all()?;
+        while self.background_work_pending()? {
+            thread::sleep(SETTLE_POLL_INTERVAL);
+        }
+        Ok(())
+    }
+
+    fn background_work_pending(&self) -> Result<bool, Error> {
+        const DB_PROPERTIES: &[&str] = &[
+            "rocksdb.num-running-flushes",
+            "rocksdb.num-running-compactions",
+        ];
+        const CF_PROPERTIES: &[&str] = &[
+            "rocksdb.mem-table-flush-pending",
+            "rocksdb.compaction-pending",
+        ];
+
+        for name in DB_PROPERTIES.iter().chain(CF_PROPERTIES) {
+            if self.property_int_value(name)? != Some(0) {
+                return Ok(true);
+            }
+        }
+        for cf in self.cfs.values() {
+            for name in CF_PROPERTIES {
+                if self.property_int_value_cf(cf, name)? != Some(0) {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Prevents RocksDB from deleting obsolete files, e.g. the inputs of
+    /// finished compactions, until `enable_file_deletions` is called. This
+    /// is useful to copy the files of a database while it is running.
+    ///
+    /// Calls are counted: deletions are only enabled again after as many
+    /// calls to `enable_file_deletions` without `force`.
+    pub fn disable_file_deletions(&self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_disable_file_deletions(self.inner,));
+        }
+        Ok(())
+    }
+
+    /// Allows RocksDB to delete obsolete files again. If `force` is true,
+    /// deletions are enabled regardless of how many times
+    /// `disable_file_deletions` has been called.
+    ///
+    /// Once deletions are enabled, the files that became obsolete in the
+    /// meantime are deleted before this returns.
+    pub fn enable_file_deletions(&self, force: bool) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_enable_file_deletions(
+                self.inner,
+                force as c_uchar,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates a checkpoint of the database in `path`, which must not exist
+    /// yet. The memtables are flushed first, so the checkpoint includes
+    /// writes that skipped the write-ahead log, e.g. with
+    /// `Options::durability_via_checkpoint`. The checkpoint can be opened as
+    /// a database of its own.
+    pub fn checkpoint_now<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        Checkpoint::new(self)?.create_checkpoint(path)
+    }
+
+    /// The sequence number of the most recent transaction.
+    pub fn latest_sequence_number(&self) -> u64 {
+        unsafe { ffi::rocksdb_get_latest_sequence_number(self.inner) }
+    }
+
+    /// Returns a cursor over the write batches applied to the database,
+    /// starting with the batch that contains the given sequence number.
+    ///
+    /// Only batches that are still in the write-ahead log can be returned,
+    /// so batches may become unavailable once a flush allows RocksDB to
+    /// delete their log files.
+    pub fn get_updates_since(&self, sequence: u64) -> Result<ChangeCursor, Error> {
+        ChangeCursor::resume(self, sequence)
+    }
+}
+
+/// Splits a list of slices into the pointer and length arrays expected by the
+/// `*v` write batch functions.
+fn slice_parts(parts: &[&[u8]]) -> (Vec<*const c_char>, Vec<size_t>) {
+    parts
+        .iter()
+        .map(|part| (part.as_ptr() as *const c_char, part.len() as size_t))
+        .unzip()
+}
+
+impl WriteBatch {
+    /// Construct a WriteBatch from its serialized representation, as returned
+    /// by [`data`](WriteBatch::data).
+    ///
+    /// This is mostly useful for replaying batches received from another
+    /// database, e.g. during replication. The values are taken as is, so a
+    /// batch that puts values cannot be written to a database opened with
+    /// `Options::set_value_checksum`; use the batches of a `ChangeCursor`
+    /// instead.
+    pub fn from_data(data: &[u8]) -> WriteBatch {
+        unsafe {
+            WriteBatch {
+                inner: ffi::rocksdb_writebatch_create_from(
+                    data.as_ptr() as *const c_char,
+                    data.len() as size_t,
+                ),
+                has_values: true,
+                value_checksum: false,
+            }
+        }
+    }
+
+    /// Creates an empty batch that appends the checksum of
+    /// `Options::set_value_checksum` to the values it puts. Batches that put
+    /// values into a database opened with that option must be created this
+    /// way.
+    pub fn with_value_checksum() -> WriteBatch {
+        let mut batch = WriteBatch::default();
+        batch.value_checksum = true;
+        batch
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { ffi::rocksdb_writebatch_count(self.inner) as usize }
+    }
+
+    /// Return WriteBatch serialized size (in bytes).
+    pub fn size_in_bytes(&self) -> usize {
+        unsafe {
+            let mut batch_size: size_t = 0;
+            ffi::rocksdb_writebatch_data(self.inner, &mut batch_size);
+            batch_size as usize
+        }
+    }
+
+    /// Return the serialized representation of this WriteBatch.
+    ///
+    /// The returned bytes can be turned back into a WriteBatch with
+    /// [`from_data`](WriteBatch::from_data).
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            let mut batch_size: size_t = 0;
+            let batch_data = ffi::rocksdb_writebatch_data(self.inner, &mut batch_size);
+            slice::from_raw_parts(batch_data as *const u8, batch_size as usize)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a value into the database under the given key.
+    pub fn put<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = with_checksum(self.value_checksum, key, value.as_ref());
+
+        self.has_values = true;
+        unsafe {
+            ffi::rocksdb_writebatch_put(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+            Ok(())
+        }
+    }
+
+    pub fn put_cf<K, V>(&mut self, cf: &ColumnFamily, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = with_checksum(self.value_checksum, key, value.as_ref());
+
+        self.has_values = true;
+        unsafe {
+            ffi::rocksdb_writebatch_put_cf(
+                self.inner,
+                cf.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+            Ok(())
+        }
+    }
+
+    /// Insert a value into the database under the given key, where both are
+    /// given as a list of parts that RocksDB concatenates.
+    ///
+    /// This avoids building a contiguous copy of a key or value that is
+    /// assembled from several buffers.
+    pub fn put_slice_parts(
+        &mut self,
+        key_parts: &[&[u8]],
+        value_parts: &[&[u8]],
+    ) -> Result<(), Error> {
+        let trailer;
+        let mut value_parts = value_parts.to_vec();
+        if self.value_checksum {
+            trailer = checksum::trailer(key_parts, &value_parts);
+            value_parts.push(&trailer);
+        }
+        let (key_ptrs, key_lens) = slice_parts(key_parts);
+        let (value_ptrs, value_lens) = slice_parts(&value_parts);
+
+        self.has_values = true;
+        unsafe {
+            ffi::rocksdb_writebatch_putv(
+                self.inner,
+                key_ptrs.len() as c_int,
+                key_ptrs.as_ptr(),
+                key_lens.as_ptr(),
+                value_ptrs.len() as c_int,
+                value_ptrs.as_ptr(),
+                value_lens.as_ptr(),
+            );
+            Ok(())
+        }
+    }
+
+    pub fn put_slice_parts_cf(
+        &mut self,
+        cf: &ColumnFamily,
+        key_parts: &[&[u8]],
+        value_parts: &[&[u8]],
+    ) -> Result<(), Error> {
+        let trailer;
+        let mut value_parts = value_parts.to_vec();
+        if self.value_checksum {
+            trailer = checksum::trailer(key_parts, &value_parts);
+            value_parts.push(&trailer);
+        }
+        let (key_ptrs, key_lens) = slice_parts(key_parts);
+        let (value_ptrs, value_lens) = slice_parts(&value_parts);
+
+        self.has_values = true;
+        unsafe {
+            ffi::rocksdb_writebatch_putv_cf(
+                self.inner,
+                cf.inner,
+                key_ptrs.len() as c_int,
+                key_ptrs.as_ptr(),
+                key_lens.as_ptr(),
+                value_ptrs.len() as c_int,
+                value_ptrs.as_ptr(),
+                value_lens.as_ptr(),
+            );
+            Ok(())
+        }
+    }
+
+    pub fn merge<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+        if self.value_checksum {
+            return Err(merge_with_checksum());
+        }
+
+        self.has_values = true;
+        unsafe {
+            ffi::rocksdb_writebatch_merge(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+            Ok(())
+        }
+    }
+
+    pub fn merge_cf<K, V>(&mut self, cf: &ColumnFamily, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+        if self.value_checksum {
+            return Err(merge_with_checksum());
+        }
+
+        self.has_values = true;
+        unsafe {
+            ffi::rocksdb_writebatch_merge_cf(
+                self.inner,
+                cf.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+            Ok(())
+        }
+    }
+
+    /// Remove the database entry for key.
+    ///
+    /// Returns an error if the key was not found.
+    pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_delete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
             );
+            Ok(())
         }
     }
 
-    pub fn compact_range_cf<S: AsRef<[u8]>, E: AsRef<[u8]>>(
-        &self,
-        cf: &ColumnFamily,
-        start: Option<S>,
-        end: Option<E>,
-    ) {
-        unsafe {
-            let start = start.as_ref().map(|s| s.as_ref());
-            let end = end.as_ref().map(|e| e.as_ref());
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &ColumnFamily, key: K) -> Result<(), Error> {
+        let key = key.as_ref();
 
-            ffi::rocksdb_compact_range_cf(
+        unsafe {
+            ffi::rocksdb_writebatch_delete_cf(
                 self.inner,
                 cf.inner,
-                opt_bytes_to_ptr(start),
-                start.map_or(0, |s| s.len()) as size_t,
-                opt_bytes_to_ptr(end),
-                end.map_or(0, |e| e.len()) as size_t,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
             );
+            Ok(())
         }
     }
 
-    pub fn set_options(&self, opts: &[(&str, &str)]) -> Result<(), Error> {
-        let copts = opts
-            .iter()
-            .map(|(name, value)| {
-                let cname = match CString::new(name.as_bytes()) {
-                    Ok(cname) => cname,
-                    Err(e) => return Err(Error::new(format!("Invalid option name `{}`", e))),
-                };
-                let cvalue = match CString::new(value.as_bytes()) {
-                    Ok(cvalue) => cvalue,
-                    Err(e) => return Err(Error::new(format!("Invalid option value: `{}`", e))),
-                };
-                Ok((cname, cvalue))
-            })
-            .collect::<Result<Vec<(CString, CString)>, Error>>()?;
+    /// Remove database entries from start key to end key.
+    ///
+    /// Removes the database entries in the range ["begin_key", "end_key"), i.e.,
+    /// including "begin_key" and excluding "end_key". It is not an error if no
+    /// keys exist in the range ["begin_key", "end_key").
+    pub fn delete_range<K: AsRef<[u8]>>(&mut self, from: K, to: K) -> Result<(), Error> {
+        let (start_key, end_key) = (from.as_ref(), to.as_ref());
 
-        let cnames: Vec<*const c_char> = copts.iter().map(|opt| opt.0.as_ptr()).collect();
-        let cvalues: Vec<*const c_char> = copts.iter().map(|opt| opt.1.as_ptr()).collect();
-        let count = opts.len() as i32;
         unsafe {
-            ffi_try!(ffi::rocksdb_set_options(
+            ffi::rocksdb_writebatch_delete_range(
                 self.inner,
-                count,
-                cnames.as_ptr(),
-                cvalues.as_ptr(),
-            ));
+                start_key.as_ptr() as *const c_char,
+                start_key.len() as size_t,
+                end_key.as_ptr() as *const c_char,
+                end_key.len() as size_t,
+            );
+            Ok(())
         }
-        Ok(())
     }
 
-    /// Retrieves a RocksDB property by name.
+    /// Remove database entries in column family from start key to end key.
     ///
-    /// For a full list of properties, see
-    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L428-L634
-    pub fn property_value(&self, name: &str) -> Result<Option<String>, Error> {
-        let prop_name = match CString::new(name) {
-            Ok(c) => c,
-            Err(e) => {
-                return Err(Error::new(format!(
-                    "Failed to convert property name to CString: {}",
-                    e
-                )));
-            }
-        };
+    /// Removes the database entries in the range ["begin_key", "end_key"), i.e.,
+    /// including "begin_key" and excluding "end_key". It is not an error if no
+    /// keys exist in the range ["begin_key", "end_key").
+    pub fn delete_range_cf<K: AsRef<[u8]>>(
+        &mut self,
+        cf: &ColumnFamily,
+        from: K,
+        to: K,
+    ) -> Result<(), Error> {
+        let (start_key, end_key) = (from.as_ref(), to.as_ref());
 
         unsafe {
-            let value = ffi::rocksdb_property_value(self.inner, prop_name.as_ptr());
-            if value.is_null() {
-                return Ok(None);
-            }
-
-            let str_value = match CStr::from_ptr(value).to_str() {
-                Ok(s) => s.to_owned(),
-                Err(e) => {
-                    return Err(Error::new(format!(
-                        "Failed to convert property value to string: {}",
-                        e
-                    )));
-                }
-            };
-
-            libc::free(value as *mut c_void);
-            Ok(Some(str_value))
+            ffi::rocksdb_writebatch_delete_range_cf(
+                self.inner,
+                cf.inner,
+                start_key.as_ptr() as *const c_char,
+                start_key.len() as size_t,
+                end_key.as_ptr() as *const c_char,
+                end_key.len() as size_t,
+            );
+            Ok(())
         }
     }
 
-    /// Retrieves a RocksDB property by name, for a specific column family.
-    ///
-    /// For a full list of properties, see
-    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L428-L634
-    pub fn property_value_cf(
-        &self,
-        cf: &ColumnFamily,
-        name: &str,
-    ) -> Result<Option<String>, Error> {
-        let prop_name = match CString::new(name) {
-            Ok(c) => c,
-            Err(e) => {
-                return Err(Error::new(format!(
-                    "Failed to convert property name to CString: {}",
-                    e
-                )));
-            }
-        };
-
+    /// Clear all updates buffered in this batch.
+    pub fn clear(&mut self) -> Result<(), Error> {
         unsafe {
-            let value = ffi::rocksdb_property_value_cf(self.inner, cf.inner, prop_name.as_ptr());
-            if value.is_null() {
-                return Ok(None);
-            }
-
-            let str_value = match CStr::from_ptr(value).to_str() {
-                Ok(s) => s.to_owned(),
-                Err(e) => {
-                    return Err(Error::new(format!(
-                        "Failed to convert property value to string: {}",
-                        e
-                    )));
-                }
-            };
-
-            libc::free(value as *mut c_void);
-            Ok(Some(str_value))
+            ffi::rocksdb_writebatch_clear(self.inner);
         }
+        self.has_values = false;
+        Ok(())
     }
+}
 
-    /// Retrieves a RocksDB property and casts it to an integer.
-    ///
-    /// For a full list of properties that return int values, see
-    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L654-L689
-    pub fn property_int_value(&self, name: &str) -> Result<Option<u64>, Error> {
-        match self.property_value(name) {
-            Ok(Some(value)) => match value.parse::<u64>() {
-                Ok(int_value) => Ok(Some(int_value)),
-                Err(e) => Err(Error::new(format!(
-                    "Failed to convert property value to int: {}",
-                    e
-                ))),
-            },
-            Ok(None) => Ok(None),
-            Err(e) => Err(e),
+impl Default for WriteBatch {
+    fn default() -> WriteBatch {
+        WriteBatch {
+            inner: unsafe { ffi::rocksdb_writebatch_create() },
+            has_values: false,
+            value_checksum: false,
         }
     }
+}
 
-    /// Retrieves a RocksDB property for a specific column family and casts it to an integer.
-    ///
-    /// For a full list of properties that return int values, see
-    /// https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L654-L689
-    pub fn property_int_value_cf(
-        &self,
-        cf: &ColumnFamily,
-        name: &str,
-    ) -> Result<Option<u64>, Error> {
-        match self.property_value_cf(cf, name) {
-            Ok(Some(value)) => match value.parse::<u64>() {
-                Ok(int_value) => Ok(Some(int_value)),
-                Err(e) => Err(Error::new(format!(
-                    "Failed to convert property value to int: {}",
-                    e
-                ))),
+impl Drop for WriteBatch {
+    fn drop(&mut self) {
+        unsafe { ffi::rocksdb_writebatch_destroy(self.inner) }
+    }
+}
+
+impl WriteBatchWithIndex {
+    /// Creates an empty batch. `reserved_bytes` is the initial capacity of
+    /// the underlying buffer. If `overwrite_keys` is true, the index only
+    /// keeps the latest update of each key, which is required for reading
+    /// merges and repeated updates of a key back from the batch.
+    pub fn new(reserved_bytes: usize, overwrite_keys: bool) -> WriteBatchWithIndex {
+        WriteBatchWithIndex {
+            inner: unsafe {
+                ffi::rocksdb_writebatch_wi_create(
+                    reserved_bytes as size_t,
+                    overwrite_keys as c_uchar,
+                )
             },
-            Ok(None) => Ok(None),
-            Err(e) => Err(e),
+            has_values: false,
+            value_checksum: false,
         }
     }
 
-    /// The sequence number of the most recent transaction.
-    pub fn latest_sequence_number(&self) -> u64 {
-        unsafe { ffi::rocksdb_get_latest_sequence_number(self.inner) }
+    /// Like `new`, but creates a batch that appends the checksum of
+    /// `Options::set_value_checksum` to the values it puts, and verifies and
+    /// strips it when reading them back. Batches that put values into a
+    /// database opened with that option must be created this way.
+    pub fn with_value_checksum(reserved_bytes: usize, overwrite_keys: bool) -> WriteBatchWithIndex {
+        let mut batch = WriteBatchWithIndex::new(reserved_bytes, overwrite_keys);
+        batch.value_checksum = true;
+        batch
     }
-}
 
-impl WriteBatch {
     pub fn len(&self) -> usize {
-        unsafe { ffi::rocksdb_writebatch_count(self.inner) as usize }
+        unsafe { ffi::rocksdb_writebatch_wi_count(self.inner) as usize }
     }
 
-    /// Return WriteBatch serialized size (in bytes).
-    pub fn size_in_bytes(&self) -> usize {
+    /// The serialized representation of the batch, as understood by
+    /// `WriteBatch::from_data`.
+    fn data(&self) -> &[u8] {
         unsafe {
             let mut batch_size: size_t = 0;
-            ffi::rocksdb_writebatch_data(self.inner, &mut batch_size);
-            batch_size as usize
+            let batch_data = ffi::rocksdb_writebatch_wi_data(self.inner, &mut batch_size);
+            slice::from_raw_parts(batch_data as *const u8, batch_size as usize)
         }
     }
 
@@ -1600,10 +3956,11 @@ impl WriteBatch {
         V: AsRef<[u8]>,
     {
         let key = key.as_ref();
-        let value = value.as_ref();
+        let value = with_checksum(self.value_checksum, key, value.as_ref());
 
+        self.has_values = true;
         unsafe {
-            ffi::rocksdb_writebatch_put(
+            ffi::rocksdb_writebatch_wi_put(
                 self.inner,
                 key.as_ptr() as *const c_char,
                 key.len() as size_t,
@@ -1620,10 +3977,11 @@ impl WriteBatch {
         V: AsRef<[u8]>,
     {
         let key = key.as_ref();
-        let value = value.as_ref();
+        let value = with_checksum(self.value_checksum, key, value.as_ref());
 
+        self.has_values = true;
         unsafe {
-            ffi::rocksdb_writebatch_put_cf(
+            ffi::rocksdb_writebatch_wi_put_cf(
                 self.inner,
                 cf.inner,
                 key.as_ptr() as *const c_char,
@@ -1642,9 +4000,13 @@ impl WriteBatch {
     {
         let key = key.as_ref();
         let value = value.as_ref();
+        if self.value_checksum {
+            return Err(merge_with_checksum());
+        }
 
+        self.has_values = true;
         unsafe {
-            ffi::rocksdb_writebatch_merge(
+            ffi::rocksdb_writebatch_wi_merge(
                 self.inner,
                 key.as_ptr() as *const c_char,
                 key.len() as size_t,
@@ -1662,9 +4024,13 @@ impl WriteBatch {
     {
         let key = key.as_ref();
         let value = value.as_ref();
+        if self.value_checksum {
+            return Err(merge_with_checksum());
+        }
 
+        self.has_values = true;
         unsafe {
-            ffi::rocksdb_writebatch_merge_cf(
+            ffi::rocksdb_writebatch_wi_merge_cf(
                 self.inner,
                 cf.inner,
                 key.as_ptr() as *const c_char,
@@ -1677,101 +4043,244 @@ impl WriteBatch {
     }
 
     /// Remove the database entry for key.
-    ///
-    /// Returns an error if the key was not found.
     pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), Error> {
         let key = key.as_ref();
 
         unsafe {
-            ffi::rocksdb_writebatch_delete(
+            ffi::rocksdb_writebatch_wi_delete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+            Ok(())
+        }
+    }
+
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &ColumnFamily, key: K) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete_cf(
+                self.inner,
+                cf.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+            Ok(())
+        }
+    }
+
+    /// Clear all updates buffered in this batch.
+    pub fn clear(&mut self) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_clear(self.inner);
+        }
+        self.has_values = false;
+        Ok(())
+    }
+
+    fn strip_value_checksum(&self, key: &[u8], value: DBVector) -> Result<DBVector, Error> {
+        if !self.value_checksum {
+            return Ok(value);
+        }
+        strip_checksum(key, value)
+    }
+
+    /// Returns the value of `key` as written to this batch, without looking
+    /// at the database. `opts` provides the merge operator for keys with
+    /// merges in the batch, and should be the options the database was
+    /// opened with.
+    pub fn get_from_batch<K: AsRef<[u8]>>(
+        &self,
+        opts: &Options,
+        key: K,
+    ) -> Result<Option<DBVector>, Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch(
                 self.inner,
+                opts.inner,
                 key.as_ptr() as *const c_char,
                 key.len() as size_t,
-            );
-            Ok(())
+                &mut val_len,
+            )) as *mut u8;
+            if val.is_null() {
+                Ok(None)
+            } else {
+                self.strip_value_checksum(key, DBVector::from_c(val, val_len))
+                    .map(Some)
+            }
         }
     }
 
-    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &ColumnFamily, key: K) -> Result<(), Error> {
+    pub fn get_from_batch_cf<K: AsRef<[u8]>>(
+        &self,
+        opts: &Options,
+        cf: &ColumnFamily,
+        key: K,
+    ) -> Result<Option<DBVector>, Error> {
         let key = key.as_ref();
 
         unsafe {
-            ffi::rocksdb_writebatch_delete_cf(
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_cf(
                 self.inner,
+                opts.inner,
                 cf.inner,
                 key.as_ptr() as *const c_char,
                 key.len() as size_t,
-            );
-            Ok(())
+                &mut val_len,
+            )) as *mut u8;
+            if val.is_null() {
+                Ok(None)
+            } else {
+                self.strip_value_checksum(key, DBVector::from_c(val, val_len))
+                    .map(Some)
+            }
         }
     }
 
-    /// Remove database entries from start key to end key.
-    ///
-    /// Removes the database entries in the range ["begin_key", "end_key"), i.e.,
-    /// including "begin_key" and excluding "end_key". It is not an error if no
-    /// keys exist in the range ["begin_key", "end_key").
-    pub fn delete_range<K: AsRef<[u8]>>(&mut self, from: K, to: K) -> Result<(), Error> {
-        let (start_key, end_key) = (from.as_ref(), to.as_ref());
+    /// Returns the value of `key` as if this batch had been written to the
+    /// database, i.e. updates in the batch take precedence over the
+    /// contents of `db`.
+    pub fn get_from_batch_and_db<K: AsRef<[u8]>>(
+        &self,
+        db: &DB,
+        key: K,
+    ) -> Result<Option<DBVector>, Error> {
+        self.get_from_batch_and_db_opt(db, key, &ReadOptions::default())
+    }
+
+    pub fn get_from_batch_and_db_opt<K: AsRef<[u8]>>(
+        &self,
+        db: &DB,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<DBVector>, Error> {
+        let key = key.as_ref();
+        db.check_batch_allowed(self.has_values, self.value_checksum)?;
 
         unsafe {
-            ffi::rocksdb_writebatch_delete_range(
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_and_db(
                 self.inner,
-                start_key.as_ptr() as *const c_char,
-                start_key.len() as size_t,
-                end_key.as_ptr() as *const c_char,
-                end_key.len() as size_t,
-            );
-            Ok(())
+                db.inner,
+                readopts.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                &mut val_len,
+            )) as *mut u8;
+            if val.is_null() {
+                Ok(None)
+            } else {
+                db.strip_value_checksum(key, DBVector::from_c(val, val_len))
+                    .map(Some)
+            }
         }
     }
 
-    /// Remove database entries in column family from start key to end key.
-    ///
-    /// Removes the database entries in the range ["begin_key", "end_key"), i.e.,
-    /// including "begin_key" and excluding "end_key". It is not an error if no
-    /// keys exist in the range ["begin_key", "end_key").
-    pub fn delete_range_cf<K: AsRef<[u8]>>(
-        &mut self,
+    pub fn get_from_batch_and_db_cf<K: AsRef<[u8]>>(
+        &self,
+        db: &DB,
         cf: &ColumnFamily,
-        from: K,
-        to: K,
-    ) -> Result<(), Error> {
-        let (start_key, end_key) = (from.as_ref(), to.as_ref());
+        key: K,
+    ) -> Result<Option<DBVector>, Error> {
+        self.get_from_batch_and_db_cf_opt(db, cf, key, &ReadOptions::default())
+    }
+
+    pub fn get_from_batch_and_db_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        db: &DB,
+        cf: &ColumnFamily,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<DBVector>, Error> {
+        let key = key.as_ref();
+        db.check_batch_allowed(self.has_values, self.value_checksum)?;
 
         unsafe {
-            ffi::rocksdb_writebatch_delete_range_cf(
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_and_db_cf(
                 self.inner,
+                db.inner,
+                readopts.inner,
                 cf.inner,
-                start_key.as_ptr() as *const c_char,
-                start_key.len() as size_t,
-                end_key.as_ptr() as *const c_char,
-                end_key.len() as size_t,
-            );
-            Ok(())
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                &mut val_len,
+            )) as *mut u8;
+            if val.is_null() {
+                Ok(None)
+            } else {
+                db.strip_value_checksum(key, DBVector::from_c(val, val_len))
+                    .map(Some)
+            }
         }
     }
 
-    /// Clear all updates buffered in this batch.
-    pub fn clear(&mut self) -> Result<(), Error> {
+    /// Returns an iterator over the contents of `base` as if this batch had
+    /// been written to the database: keys put in the batch override those of
+    /// `base`, and keys deleted in the batch are skipped.
+    ///
+    /// The batch should be created with `overwrite_keys` set (and with
+    /// `with_value_checksum` if the database uses `Options::set_value_checksum`),
+    /// and must not be modified while the iterator is alive. The position of `base` is
+    /// discarded; the returned iterator starts at `mode`.
+    ///
+    /// ```
+    /// use rocksdb::{IteratorMode, Options, WriteBatchWithIndex, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_iterator_with_base";
+    /// {
+    ///     let db = DB::open_default(path).unwrap();
+    ///     db.put(b"k1", b"v1").unwrap();
+    ///
+    ///     let mut batch = WriteBatchWithIndex::new(0, true);
+    ///     batch.put(b"k2", b"v2").unwrap();
+    ///     let base = db.iterator(IteratorMode::Start).unwrap();
+    ///     let keys: Vec<_> = batch
+    ///         .iterator_with_base(base, IteratorMode::Start)
+    ///         .map(|(key, _)| key)
+    ///         .collect();
+    ///     assert_eq!(keys.len(), 2);
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn iterator_with_base<'a>(
+        &'a self,
+        base: DBIterator<'a>,
+        mode: IteratorMode,
+    ) -> DBIterator<'a> {
+        let mut raw = base.raw;
         unsafe {
-            ffi::rocksdb_writebatch_clear(self.inner);
+            // The merged iterator takes ownership of the base iterator.
+            raw.inner = ffi::rocksdb_writebatch_wi_create_iterator_with_base(self.inner, raw.inner);
         }
-        Ok(())
+        DBIterator::from_raw(raw, mode)
     }
-}
 
-impl Default for WriteBatch {
-    fn default() -> WriteBatch {
-        WriteBatch {
-            inner: unsafe { ffi::rocksdb_writebatch_create() },
+    /// Like `iterator_with_base`, for a base iterator over `cf`.
+    pub fn iterator_with_base_cf<'a>(
+        &'a self,
+        cf: &ColumnFamily,
+        base: DBIterator<'a>,
+        mode: IteratorMode,
+    ) -> DBIterator<'a> {
+        let mut raw = base.raw;
+        unsafe {
+            raw.inner = ffi::rocksdb_writebatch_wi_create_iterator_with_base_cf(
+                self.inner, raw.inner, cf.inner,
+            );
         }
+        DBIterator::from_raw(raw, mode)
     }
 }
 
-impl Drop for WriteBatch {
+impl Drop for WriteBatchWithIndex {
     fn drop(&mut self) {
-        unsafe { ffi::rocksdb_writebatch_destroy(self.inner) }
+        unsafe { ffi::rocksdb_writebatch_wi_destroy(self.inner) }
     }
 }
 
@@ -1802,13 +4311,33 @@ impl ReadOptions {
     // TODO add snapshot setting here
     // TODO add snapshot wrapper structs with proper destructors;
     // that struct needs an "iterator" impl too.
-    #[allow(dead_code)]
-    fn fill_cache(&mut self, v: bool) {
+    /// Specify whether the blocks read for this read or iteration should be
+    /// cached in memory. Callers may wish to set this to false for bulk scans.
+    ///
+    /// Default: true
+    pub fn fill_cache(&mut self, v: bool) {
         unsafe {
             ffi::rocksdb_readoptions_set_fill_cache(self.inner, v as c_uchar);
         }
     }
 
+    /// Specify which storage tiers a read may be served from. Reads that can't
+    /// be served from the allowed tiers fail with an `Incomplete` status.
+    ///
+    /// Default: `ReadTier::All`
+    ///
+    /// ```
+    /// use rocksdb::{ReadOptions, ReadTier};
+    ///
+    /// let mut opts = ReadOptions::default();
+    /// opts.set_read_tier(ReadTier::BlockCache);
+    /// ```
+    pub fn set_read_tier(&mut self, tier: ReadTier) {
+        unsafe {
+            ffi::rocksdb_readoptions_set_read_tier(self.inner, tier as c_int);
+        }
+    }
+
     fn set_snapshot(&mut self, snapshot: &Snapshot) {
         unsafe {
             ffi::rocksdb_readoptions_set_snapshot(self.inner, snapshot.inner);
@@ -1832,6 +4361,22 @@ impl ReadOptions {
         );
     }
 
+    /// Set the lower bound for an iterator.
+    /// The lower bound itself is included on the iteration result.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for
+    /// [`set_iterate_upper_bound`](ReadOptions::set_iterate_upper_bound) apply.
+    pub unsafe fn set_iterate_lower_bound<K: AsRef<[u8]>>(&mut self, key: K) {
+        let key = key.as_ref();
+        ffi::rocksdb_readoptions_set_iterate_lower_bound(
+            self.inner,
+            key.as_ptr() as *const c_char,
+            key.len() as size_t,
+        );
+    }
+
     pub fn set_prefix_same_as_start(&mut self, v: bool) {
         unsafe { ffi::rocksdb_readoptions_set_prefix_same_as_start(self.inner, v as c_uchar) }
     }
@@ -1856,6 +4401,46 @@ impl ReadOptions {
             ffi::rocksdb_readoptions_set_readahead_size(self.inner, v as size_t);
         }
     }
+
+    /// If true, keys deleted using the `delete_range` family of methods
+    /// will not be hidden from reads and iterators.
+    ///
+    /// This is meant as a debugging and recovery aid only: it makes reads
+    /// behave as if range tombstones had never been written.
+    ///
+    /// Default: false
+    ///
+    /// ```
+    /// use rocksdb::{ReadOptions};
+    ///
+    /// let mut opts = ReadOptions::default();
+    /// opts.set_ignore_range_deletions(true);
+    /// ```
+    pub fn set_ignore_range_deletions(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_readoptions_set_ignore_range_deletions(self.inner, v as c_uchar);
+        }
+    }
+
+    /// If non-zero, an iterator gives up after skipping this many internal
+    /// keys (e.g. deleted or overwritten entries) while looking for the next
+    /// visible key. It then becomes invalid and `status` returns an
+    /// `Incomplete` error, instead of the iterator grinding through a long
+    /// run of tombstones.
+    ///
+    /// Default: 0 (no limit)
+    ///
+    /// ```
+    /// use rocksdb::{ReadOptions};
+    ///
+    /// let mut opts = ReadOptions::default();
+    /// opts.set_max_skippable_internal_keys(1000);
+    /// ```
+    pub fn set_max_skippable_internal_keys(&mut self, num: u64) {
+        unsafe {
+            ffi::rocksdb_readoptions_set_max_skippable_internal_keys(self.inner, num);
+        }
+    }
 }
 
 impl Default for ReadOptions {
@@ -1941,6 +4526,94 @@ impl DBVector {
     }
 }
 
+fn multi_get_keys<K: AsRef<[u8]>>(keys: &[K]) -> (Vec<*const c_char>, Vec<size_t>) {
+    keys.iter()
+        .map(|k| {
+            let k = k.as_ref();
+            (k.as_ptr() as *const c_char, k.len() as size_t)
+        })
+        .unzip()
+}
+
+/// The output buffers filled in by `rocksdb_multi_get(_cf)`.
+struct MultiGetValues {
+    values: Vec<*mut c_char>,
+    sizes: Vec<size_t>,
+    errors: Vec<*mut c_char>,
+}
+
+impl MultiGetValues {
+    fn new(len: usize) -> MultiGetValues {
+        MultiGetValues {
+            values: vec![ptr::null_mut(); len],
+            sizes: vec![0; len],
+            errors: vec![ptr::null_mut(); len],
+        }
+    }
+
+    /// Collects the values of a multi get on `db`, verifying and stripping
+    /// their checksums like `DB::get`.
+    fn into_results<K: AsRef<[u8]>>(
+        self,
+        db: &DB,
+        keys: &[K],
+    ) -> Vec<Result<Option<DBVector>, Error>> {
+        self.values
+            .into_iter()
+            .zip(self.sizes)
+            .zip(self.errors)
+            .zip(keys)
+            .map(|(((value, size), error), key)| {
+                if !error.is_null() {
+                    Err(Error::new(error_message(error)))
+                } else if value.is_null() {
+                    Ok(None)
+                } else {
+                    let value = unsafe { DBVector::from_c(value as *mut u8, size) };
+                    db.strip_value_checksum(key.as_ref(), value).map(Some)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns `value` followed by its checksum if `enabled` is true.
+fn with_checksum<'v>(enabled: bool, key: &[u8], value: &'v [u8]) -> Cow<'v, [u8]> {
+    if enabled {
+        Cow::Owned(checksum::append(key, value))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Verifies the checksum at the end of `value` and hides it.
+fn strip_checksum(key: &[u8], mut value: DBVector) -> Result<DBVector, Error> {
+    match checksum::verify(key, &value) {
+        Some(len) => {
+            value.len = len;
+            Ok(value)
+        }
+        None => Err(checksum_mismatch()),
+    }
+}
+
+fn merge_with_checksum() -> Error {
+    Error::new("Merge is not supported with value checksums enabled".to_owned())
+}
+
+fn checksum_mismatch() -> Error {
+    Error::with_kind(
+        ErrorKind::ChecksumMismatch,
+        "Value does not match its checksum".to_owned(),
+    )
+}
+
+/// RocksDB reports a held `LOCK` file as an IO error naming the lock file.
+fn is_lock_error(e: &Error) -> bool {
+    let message = e.as_ref();
+    message.starts_with("IO error") && message.contains("LOCK")
+}
+
 fn to_cpath<P: AsRef<Path>>(path: P) -> Result<CString, Error> {
     match CString::new(path.as_ref().to_string_lossy().as_bytes()) {
         Ok(c) => Ok(c),
@@ -1950,6 +4623,157 @@ fn to_cpath<P: AsRef<Path>>(path: P) -> Result<CString, Error> {
     }
 }
 
+/// The most important properties of a database at one point in time, as
+/// returned by `DB::operational_snapshot`.
+///
+/// Apart from `files_per_level`, the values are those of the default column
+/// family, or of the whole database for the DB-wide properties. The
+/// properties are read one after another, so they may not be consistent
+/// with each other while writes or compactions are running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationalSnapshot {
+    /// `rocksdb.estimate-num-keys`
+    pub estimate_num_keys: u64,
+    /// `rocksdb.live-sst-files-size`
+    pub live_sst_size: u64,
+    /// `rocksdb.estimate-pending-compaction-bytes`
+    pub pending_compaction_bytes: u64,
+    /// `rocksdb.num-running-compactions`
+    pub running_compactions: u64,
+    /// `rocksdb.num-running-flushes`
+    pub running_flushes: u64,
+    /// `rocksdb.num-files-at-level<N>` of every level of the default
+    /// column family, starting with level 0.
+    pub files_per_level: Vec<u64>,
+    /// `rocksdb.block-cache-usage`, if the column family uses a block cache.
+    pub block_cache_usage: Option<u64>,
+    /// `rocksdb.background-errors`, the number of background errors since
+    /// the database was opened.
+    pub background_errors: u64,
+}
+
+/// A cursor over the write batches in the write-ahead log, in sequence order.
+///
+/// The cursor keeps track of the sequence number following the last batch it
+/// returned, so a consumer can persist `next_sequence` and later pick up
+/// where it left off with `ChangeCursor::resume`, without gaps or
+/// duplicates. Batches written after the cursor reached the end of the log
+/// are not returned; resume a new cursor to see them.
+///
+/// ```
+/// use rocksdb::{ChangeCursor, Options, DB};
+///
+/// let path = "_path_for_rocksdb_storage_change_cursor";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     db.put(b"k1", b"v1").unwrap();
+///     db.put(b"k2", b"v2").unwrap();
+///
+///     let mut cursor = db.get_updates_since(0).unwrap();
+///     let (sequence, batch) = cursor.next().unwrap().unwrap();
+///     assert_eq!(sequence, 1);
+///     assert_eq!(batch.len(), 1);
+///
+///     let next_sequence = cursor.next_sequence();
+///     drop(cursor);
+///
+///     let mut cursor = ChangeCursor::resume(&db, next_sequence).unwrap();
+///     let (sequence, _) = cursor.next().unwrap().unwrap();
+///     assert_eq!(sequence, 2);
+///     assert!(cursor.next().is_none());
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct ChangeCursor<'a> {
+    inner: *mut ffi::rocksdb_wal_iterator_t,
+    next_sequence: u64,
+    value_checksum: bool,
+    db: PhantomData<&'a DB>,
+}
+
+impl<'a> ChangeCursor<'a> {
+    /// Creates a cursor that starts with the batch containing `sequence`,
+    /// usually a value previously returned by `next_sequence`.
+    pub fn resume(db: &'a DB, sequence: u64) -> Result<ChangeCursor<'a>, Error> {
+        let mut cursor = ChangeCursor {
+            inner: ptr::null_mut(),
+            next_sequence: sequence,
+            value_checksum: db.value_checksum,
+            db: PhantomData,
+        };
+        // RocksDB refuses to start past the latest sequence number, which is
+        // exactly where a fully caught up consumer resumes from.
+        if sequence <= db.latest_sequence_number() {
+            cursor.inner = unsafe {
+                ffi_try!(ffi::rocksdb_get_updates_since(
+                    db.inner,
+                    sequence,
+                    ptr::null(),
+                ))
+            };
+        }
+        Ok(cursor)
+    }
+
+    /// The sequence number following the last batch returned by this cursor.
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    fn status(&self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_wal_iter_status(self.inner,));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for ChangeCursor<'a> {
+    type Item = Result<(u64, WriteBatch), Error>;
+
+    fn next(&mut self) -> Option<Result<(u64, WriteBatch), Error>> {
+        while !self.inner.is_null() {
+            unsafe {
+                if ffi::rocksdb_wal_iter_valid(self.inner) == 0 {
+                    let status = self.status();
+                    ffi::rocksdb_wal_iter_destroy(self.inner);
+                    self.inner = ptr::null_mut();
+                    return status.err().map(Err);
+                }
+
+                let mut sequence: u64 = 0;
+                let batch = WriteBatch {
+                    inner: ffi::rocksdb_wal_iter_get_batch(self.inner, &mut sequence),
+                    has_values: true,
+                    // The values are logged as stored.
+                    value_checksum: self.value_checksum,
+                };
+                ffi::rocksdb_wal_iter_next(self.inner);
+
+                // The first batch may have been returned before, if it
+                // starts ahead of the sequence number the cursor resumed at.
+                let end = sequence + batch.len() as u64;
+                if end <= self.next_sequence {
+                    continue;
+                }
+                self.next_sequence = end;
+                return Some(Ok((sequence, batch)));
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Drop for ChangeCursor<'a> {
+    fn drop(&mut self) {
+        if !self.inner.is_null() {
+            unsafe {
+                ffi::rocksdb_wal_iter_destroy(self.inner);
+            }
+        }
+    }
+}
+
 /// Wrapper around RocksDB PinnableSlice struct.
 ///
 /// With a pinnable slice, we can directly leverage in-memory data within
@@ -1957,6 +4781,9 @@ fn to_cpath<P: AsRef<Path>>(path: P) -> Result<CString, Error> {
 /// returned raw pointer and ensures proper finalization work.
 pub struct DBPinnableSlice<'a> {
     ptr: *mut ffi::rocksdb_pinnableslice_t,
+    /// Bytes at the end of the value that are not part of it, i.e. the
+    /// checksum added with `Options::set_value_checksum`.
+    trailer_len: usize,
     db: PhantomData<&'a DB>,
 }
 
@@ -1974,7 +4801,7 @@ impl<'a> Deref for DBPinnableSlice<'a> {
         unsafe {
             let mut val_len: size_t = 0;
             let val = ffi::rocksdb_pinnableslice_value(self.ptr, &mut val_len) as *mut u8;
-            slice::from_raw_parts(val, val_len)
+            slice::from_raw_parts(val, val_len - self.trailer_len)
         }
     }
 }
@@ -1995,6 +4822,7 @@ impl<'a> DBPinnableSlice<'a> {
     pub unsafe fn from_c(ptr: *mut ffi::rocksdb_pinnableslice_t) -> DBPinnableSlice<'a> {
         DBPinnableSlice {
             ptr,
+            trailer_len: 0,
             db: PhantomData,
         }
     }
@@ -2115,7 +4943,7 @@ fn iterator_test() {
         assert!(p.is_ok());
         let p = db.put(b"k3", b"v3333");
         assert!(p.is_ok());
-        let iter = db.iterator(IteratorMode::Start);
+        let iter = db.iterator(IteratorMode::Start).unwrap();
         for (k, v) in iter {
             println!(
                 "Hello {}: {}",
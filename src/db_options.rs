@@ -14,7 +14,7 @@
 
 use std::ffi::{CStr, CString};
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use libc::{self, c_int, c_uchar, c_uint, c_void, size_t};
 
@@ -26,8 +26,9 @@ use merge_operator::{
 };
 use slice_transform::SliceTransform;
 use {
-    BlockBasedIndexType, BlockBasedOptions, DBCompactionStyle, DBCompressionType, DBRecoveryMode,
-    FlushOptions, MemtableFactory, Options, PlainTableFactoryOptions, WriteOptions,
+    AccessHint, BlockBasedIndexType, BlockBasedOptions, BottommostLevelCompaction, CompactOptions,
+    DBCompactionStyle, DBCompressionType, DBRecoveryMode, FlushOptions, IngestExternalFileOptions,
+    MemtableFactory, Options, PlainTableFactoryOptions, WriteOptions,
 };
 
 pub fn new_cache(capacity: size_t) -> *mut ffi::rocksdb_cache_t {
@@ -70,6 +71,22 @@ impl Drop for FlushOptions {
     }
 }
 
+impl Drop for CompactOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_compactoptions_destroy(self.inner);
+        }
+    }
+}
+
+impl Drop for IngestExternalFileOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_destroy(self.inner);
+        }
+    }
+}
+
 impl Drop for WriteOptions {
     fn drop(&mut self) {
         unsafe {
@@ -287,6 +304,150 @@ impl Options {
         }
     }
 
+    /// Sets the maximum size of a value passed to the `put` and `merge`
+    /// methods of `DB`, which fail with `ErrorKind::ValueTooLarge` for
+    /// larger values. This is a safeguard implemented by this crate, not a
+    /// RocksDB option: it is not persisted, and values written through a
+    /// `WriteBatch` are not checked. `0` means no limit.
+    ///
+    /// Default: `0`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_max_value_size(16 * 1024 * 1024);
+    /// ```
+    pub fn set_max_value_size(&mut self, size: usize) {
+        self.max_value_size = size;
+    }
+
+    /// If true, the `put` methods of `DB` append a CRC32c checksum of the key
+    /// and value to every value, and the `get` methods verify and strip it
+    /// again, failing with `ErrorKind::ChecksumMismatch` if the value was
+    /// corrupted or ended up under another key. Like `set_max_value_size`,
+    /// this is implemented by this crate and not persisted by RocksDB, so a
+    /// database must always be opened with the same setting.
+    ///
+    /// The checksum is verified and stripped by `get`, `get_pinned`,
+    /// `multi_get`, `get_into` and snapshot reads. Iterators strip it too; a
+    /// value that does not match is returned as is and makes the iterator's
+    /// `status` fail. Write batches add it if they are created with
+    /// `WriteBatch::with_value_checksum` or
+    /// `WriteBatchWithIndex::with_value_checksum`, and other batches that put
+    /// values are rejected with an error. An `SstFileWriter` created with
+    /// these options adds it as well. Merges are rejected, since a merge
+    /// operator would not preserve the checksum.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_value_checksum(true);
+    /// ```
+    pub fn set_value_checksum(&mut self, enabled: bool) {
+        self.value_checksum = enabled;
+    }
+
+    /// A debugging aid for append-only workloads, e.g. keys starting with a
+    /// timestamp: if not zero, the `put` methods of `DB` fail if the first
+    /// `prefix_len` bytes of the key compare lower, bytewise, than those of
+    /// the previous key written to the same column family. Like
+    /// `set_max_value_size`, this is implemented by this crate: only `put`
+    /// is checked, and the last prefix is forgotten when the database is
+    /// closed. A prefix is only recorded once its put succeeded.
+    ///
+    /// To keep the check consistent with the order in which RocksDB applies
+    /// the writes, puts are serialized while this is enabled, so it is not
+    /// meant for write-heavy multi-threaded production use.
+    ///
+    /// Default: `0` (disabled)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_enforce_monotonic_prefix(8);
+    /// ```
+    pub fn set_enforce_monotonic_prefix(&mut self, prefix_len: usize) {
+        self.monotonic_prefix_len = prefix_len;
+    }
+
+    /// Makes the `DB` methods that don't take `WriteOptions` skip the
+    /// write-ahead log, for a cache-like database that is made durable by
+    /// calling `DB::checkpoint_now` from time to time instead. Writes made
+    /// since the last checkpoint are lost if the process crashes. Like
+    /// `set_max_value_size`, this is implemented by this crate: it applies
+    /// until `DB::set_default_write_options` is called, and not to methods
+    /// given explicit `WriteOptions`.
+    ///
+    /// RocksDB's `manual_wal_flush` option is not set: the bundled C API does
+    /// not expose it, and with writes skipping the log there is nothing left
+    /// for it to buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{Options, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_durability_via_checkpoint";
+    /// let checkpoint_path = "_path_for_rocksdb_storage_durability_via_checkpoint_cp";
+    /// {
+    ///     let mut opts = Options::default();
+    ///     opts.create_if_missing(true);
+    ///     opts.durability_via_checkpoint();
+    ///     let db = DB::open(&opts, path).unwrap();
+    ///     db.put(b"k1", b"v1").unwrap();
+    ///     db.checkpoint_now(checkpoint_path).unwrap();
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// let _ = DB::destroy(&Options::default(), checkpoint_path);
+    /// ```
+    pub fn durability_via_checkpoint(&mut self) {
+        self.disable_wal = true;
+    }
+
+    /// Tunes the options for loading a lot of data at once: automatic
+    /// compactions are disabled, and level 0 may hold any number of files
+    /// without slowing down writes. Compact the database manually once the
+    /// data is loaded, e.g. with `DB::compact_range`.
+    ///
+    /// This also lowers the number of levels to 2, so it should only be
+    /// used for a new database. See `BulkLoader` for a complete pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.prepare_for_bulk_load();
+    /// ```
+    pub fn prepare_for_bulk_load(&mut self) {
+        unsafe {
+            ffi::rocksdb_options_prepare_for_bulk_load(self.inner);
+        }
+    }
+
+    /// Sets a merge operator. `full_merge_fn` combines the existing value
+    /// of a key, if any, with its pending merge operands, and is used on
+    /// reads and whenever a compaction reaches the base value.
+    ///
+    /// `partial_merge_fn`, if given, combines merge operands into a single
+    /// operand when there is no base value yet, e.g. during flushes. It is
+    /// called with all pending operands of a key at once (RocksDB's
+    /// `PartialMergeMulti`) rather than pairwise, and always receives `None`
+    /// as the existing value. Without it, `full_merge_fn` is used for both.
+    ///
+    /// See the `merge_operator` module for an example.
     pub fn set_merge_operator(
         &mut self,
         name: &str,
@@ -312,6 +473,65 @@ impl Options {
         }
     }
 
+    /// Sets a merge operator which interprets values and merge operands as
+    /// 64-bit unsigned integers (8 bytes, little-endian) and adds them up.
+    /// Use it together with `DB::upsert` to maintain counters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{Options, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_uint64add";
+    /// {
+    ///     let mut opts = Options::default();
+    ///     opts.create_if_missing(true);
+    ///     opts.set_uint64add_merge_operator();
+    ///     let db = DB::open(&opts, path).unwrap();
+    ///     db.upsert(b"counter", 1).unwrap();
+    ///     db.upsert(b"counter", 2).unwrap();
+    ///     let value = db.get(b"counter").unwrap().unwrap();
+    ///     assert_eq!(&*value, &3u64.to_le_bytes());
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn set_uint64add_merge_operator(&mut self) {
+        self.set_merge_operator("UInt64AddOperator", merge_operator::uint64add_merge, None);
+    }
+
+    /// Sets a merge operator which maintains a set of byte strings per key.
+    /// Values and merge operands are sets encoded with
+    /// `merge_operator::encode_sorted_set`, and merging them produces their
+    /// sorted union without duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::merge_operator::{decode_sorted_set, encode_sorted_set};
+    /// use rocksdb::{Options, DB};
+    ///
+    /// let path = "_path_for_rocksdb_storage_sorted_set_union";
+    /// {
+    ///     let mut opts = Options::default();
+    ///     opts.create_if_missing(true);
+    ///     opts.set_sorted_set_union_merge_operator();
+    ///     let db = DB::open(&opts, path).unwrap();
+    ///     db.merge(b"tags", encode_sorted_set(&["red", "blue"])).unwrap();
+    ///     db.merge(b"tags", encode_sorted_set(&["green", "red"])).unwrap();
+    ///     let value = db.get(b"tags").unwrap().unwrap();
+    ///     let tags = vec![&b"blue"[..], b"green", b"red"];
+    ///     assert_eq!(decode_sorted_set(&value), tags);
+    /// }
+    /// let _ = DB::destroy(&Options::default(), path);
+    /// ```
+    pub fn set_sorted_set_union_merge_operator(&mut self) {
+        self.set_merge_operator(
+            "SortedSetUnionOperator",
+            merge_operator::sorted_set_union_merge,
+            Some(merge_operator::sorted_set_union_merge),
+        );
+    }
+
     #[deprecated(
         since = "0.5.0",
         note = "add_merge_operator has been renamed to set_merge_operator"
@@ -330,6 +550,10 @@ impl Options {
     ///
     /// If multi-threaded compaction is used, `filter_fn` may be called multiple times
     /// simultaneously.
+    ///
+    /// With `enable_statistics`, the number of entries removed by the filter is
+    /// counted by the `rocksdb.compaction.key.drop.user` ticker, which
+    /// `DB::collect_metrics` reports as `rocksdb_compaction_key_drop_user`.
     pub fn set_compaction_filter<F>(&mut self, name: &str, filter_fn: F)
     where
         F: CompactionFilterFn + Send + 'static,
@@ -460,6 +684,24 @@ impl Options {
         }
     }
 
+    /// Same as `set_bytes_per_sync`, but applies to WAL files.
+    ///
+    /// Default: `0`, turned off
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_wal_bytes_per_sync(1024 * 1024);
+    /// ```
+    pub fn set_wal_bytes_per_sync(&mut self, nbytes: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_wal_bytes_per_sync(self.inner, nbytes);
+        }
+    }
+
     /// If true, allow multi-writers to update mem tables in parallel.
     /// Only some memtable_factory-s support concurrent writes; currently it
     /// is implemented only for SkipListFactory.  Concurrent memtable writes
@@ -483,6 +725,53 @@ impl Options {
         }
     }
 
+    /// If true, a put of a key that is already in the memtable overwrites
+    /// the existing value in place when the new value is not larger,
+    /// instead of inserting a new entry. This keeps the memtable small for
+    /// workloads that overwrite the same keys over and over.
+    ///
+    /// This requires the default skip-list memtable and is not compatible
+    /// with concurrent memtable writes, so
+    /// `set_allow_concurrent_memtable_write(false)` must be set as well or
+    /// the database fails to open. Snapshots do not see the overwritten
+    /// values, since only the latest value of a key is kept in the memtable.
+    ///
+    /// Default: false
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_allow_concurrent_memtable_write(false);
+    /// opts.set_inplace_update_support(true);
+    /// ```
+    pub fn set_inplace_update_support(&mut self, enabled: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_inplace_update_support(self.inner, enabled as c_uchar);
+        }
+    }
+
+    /// Sets the number of locks used for in-place updates. Only used when
+    /// `set_inplace_update_support` is enabled.
+    ///
+    /// Default: 10000
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_inplace_update_num_locks(1000);
+    /// ```
+    pub fn set_inplace_update_num_locks(&mut self, num: usize) {
+        unsafe {
+            ffi::rocksdb_options_set_inplace_update_num_locks(self.inner, num);
+        }
+    }
+
     /// Enable direct I/O mode for reading
     /// they may or may not improve performance depending on the use case
     ///
@@ -720,6 +1009,33 @@ impl Options {
         }
     }
 
+    /// If true, RocksDB picks the target size of each level dynamically,
+    /// working back from the size of the last level, instead of using
+    /// max_bytes_for_level_base and max_bytes_for_level_multiplier from
+    /// level-1 up. This bounds space amplification at roughly
+    /// 1.1x for a stable database size.
+    ///
+    /// Turning this on for an existing database is only safe when the data
+    /// already fits the dynamic level targets; otherwise compactions may
+    /// keep data in unexpected levels until the shape converges. It is best
+    /// enabled on new databases.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_level_compaction_dynamic_level_bytes(true);
+    /// ```
+    pub fn set_level_compaction_dynamic_level_bytes(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_level_compaction_dynamic_level_bytes(self.inner, v as c_uchar);
+        }
+    }
+
     /// Default: `10`
     ///
     /// # Example
@@ -781,6 +1097,31 @@ impl Options {
         }
     }
 
+    /// Sets the maximum number of bytes in all compacted input files of a
+    /// single compaction. RocksDB avoids picking compactions larger than
+    /// this, e.g. by leaving out overlapping files of the output level, so
+    /// large compactions are split into several smaller jobs. It should be
+    /// a good deal larger than target_file_size_base, since a compaction
+    /// always includes at least one whole input file.
+    ///
+    /// Default: `0`, which means target_file_size_base * 25
+    ///
+    /// Dynamically changeable through SetOptions() API
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_max_compaction_bytes(1024 * 1024 * 1024);
+    /// ```
+    pub fn set_max_compaction_bytes(&mut self, nbytes: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_max_compaction_bytes(self.inner, nbytes);
+        }
+    }
+
     /// Sets the minimum number of write buffers that will be merged together
     /// before writing to storage.  If set to `1`, then
     /// all write buffers are flushed to L0 as individual files and this increases
@@ -1120,6 +1461,25 @@ impl Options {
         unsafe { ffi::rocksdb_options_set_advise_random_on_open(self.inner, advise as c_uchar) }
     }
 
+    /// Specifies the file access pattern hint given to the OS once a
+    /// compaction starts, applied to all of the compaction's input files.
+    ///
+    /// Default: `AccessHint::Normal`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{AccessHint, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_access_hint_on_compaction_start(AccessHint::Sequential);
+    /// ```
+    pub fn set_access_hint_on_compaction_start(&mut self, hint: AccessHint) {
+        unsafe {
+            ffi::rocksdb_options_set_access_hint_on_compaction_start(self.inner, hint as c_int);
+        }
+    }
+
     /// Sets the number of levels for this database.
     pub fn set_num_levels(&mut self, n: c_int) {
         unsafe {
@@ -1149,6 +1509,27 @@ impl Options {
         }
     }
 
+    /// Controls the locality of bloom filter probes to improve CPU cache hit
+    /// rate. With a value of 0 probes are spread over the whole filter; with a
+    /// value of `n` they are confined to `n` cache lines (this only applies to
+    /// the memtable prefix bloom filter and plain table filters).
+    ///
+    /// Default: `0`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_bloom_locality(1);
+    /// ```
+    pub fn set_bloom_locality(&mut self, locality: u32) {
+        unsafe {
+            ffi::rocksdb_options_set_bloom_locality(self.inner, locality);
+        }
+    }
+
     /// Specifies the absolute path of the directory the
     /// write-ahead log (WAL) should be written to.
     ///
@@ -1169,11 +1550,88 @@ impl Options {
         }
     }
 
+    /// Specifies the directory the info log (`LOG`) files should be written
+    /// to. The files are prefixed with the database's absolute path, so
+    /// several databases can share one log directory.
+    ///
+    /// Default: same directory as the database
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_db_log_dir("/path/to/dir");
+    /// ```
+    pub fn set_db_log_dir<P: AsRef<Path>>(&mut self, path: P) {
+        let p = CString::new(path.as_ref().to_string_lossy().as_bytes()).unwrap();
+        unsafe {
+            ffi::rocksdb_options_set_db_log_dir(self.inner, p.as_ptr());
+        }
+    }
+
+    /// Sets a list of paths where SST files can be put into, with their target
+    /// sizes. Newer data is placed into paths specified earlier in the list
+    /// while older data gradually moves to paths specified later.
+    ///
+    /// For example, with `[("/flash", 10GB), ("/hdd", 2TB)]` up to 10GB of the
+    /// most recent data goes to /flash and the rest to /hdd. With leveled
+    /// compaction placement is decided per level, so a path may end up above
+    /// its target size. The WAL is not affected; see `set_wal_dir` for that.
+    ///
+    /// Default: empty, meaning all SST files are put in the database directory
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    /// use std::path::PathBuf;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_db_paths(&[
+    ///     (PathBuf::from("/path/to/flash"), 10 << 30),
+    ///     (PathBuf::from("/path/to/hdd"), 2 << 40),
+    /// ]);
+    /// ```
+    pub fn set_db_paths(&mut self, paths: &[(PathBuf, u64)]) {
+        let c_paths: Vec<_> = paths
+            .iter()
+            .map(|(path, target_size)| {
+                let p = CString::new(path.to_string_lossy().as_bytes()).unwrap();
+                unsafe { ffi::rocksdb_dbpath_create(p.as_ptr(), *target_size) }
+            })
+            .collect();
+        unsafe {
+            ffi::rocksdb_options_set_db_paths(
+                self.inner,
+                c_paths.as_ptr() as *mut *const ffi::rocksdb_dbpath_t,
+                c_paths.len() as size_t,
+            );
+            for path in c_paths {
+                ffi::rocksdb_dbpath_destroy(path);
+            }
+        }
+    }
+
     /// If true, then DB::Open() will not update the statistics used to optimize
     /// compaction decision by loading table properties from many files.
     /// Turning off this feature will improve DBOpen time especially in disk environment.
     ///
+    /// Note that the skipped statistics are only used to guide compaction, so
+    /// the first compactions after opening may be picked from slightly stale
+    /// information until the statistics catch up.
+    ///
     /// Default: false
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_skip_stats_update_on_db_open(true);
+    /// ```
     pub fn set_skip_stats_update_on_db_open(&mut self, skip: bool) {
         unsafe {
             ffi::rocksdb_options_set_skip_stats_update_on_db_open(self.inner, skip as c_uchar);
@@ -1187,6 +1645,68 @@ impl Options {
         }
     }
 
+    /// Specifies the maximal size of the info log file. If the log file is
+    /// larger than this, a new info log file will be created.
+    ///
+    /// If set to 0, all logs will be written to one log file.
+    ///
+    /// Default: 0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_max_log_file_size(1 << 20);
+    /// ```
+    pub fn set_max_log_file_size(&mut self, size: usize) {
+        unsafe {
+            ffi::rocksdb_options_set_max_log_file_size(self.inner, size);
+        }
+    }
+
+    /// Specifies the time interval (in seconds) for the info log file to roll.
+    /// If set to 0, the log file is never rolled based on time.
+    ///
+    /// Default: 0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_log_file_time_to_roll(24 * 60 * 60);
+    /// ```
+    pub fn set_log_file_time_to_roll(&mut self, secs: usize) {
+        unsafe {
+            ffi::rocksdb_options_set_log_file_time_to_roll(self.inner, secs);
+        }
+    }
+
+    /// Recycle WAL files: up to this many obsolete log files are kept around
+    /// and overwritten instead of creating new ones, which avoids the cost of
+    /// allocating fresh files on some file systems.
+    ///
+    /// Note that this applies to the write-ahead log, not the info log.
+    ///
+    /// Default: 0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_recycle_log_file_num(4);
+    /// ```
+    pub fn set_recycle_log_file_num(&mut self, num: usize) {
+        unsafe {
+            ffi::rocksdb_options_set_recycle_log_file_num(self.inner, num);
+        }
+    }
+
     /// Allow the OS to mmap file for writing.
     ///
     /// Default: false
@@ -1231,7 +1751,13 @@ impl Default for Options {
             if opts.is_null() {
                 panic!("Could not create RocksDB options");
             }
-            Options { inner: opts }
+            Options {
+                inner: opts,
+                max_value_size: 0,
+                value_checksum: false,
+                disable_wal: false,
+                monotonic_prefix_len: 0,
+            }
         }
     }
 }
@@ -1298,6 +1824,199 @@ impl Default for WriteOptions {
     }
 }
 
+impl CompactOptions {
+    pub fn new() -> CompactOptions {
+        CompactOptions::default()
+    }
+
+    /// If true, no other compaction will run at the same time as this
+    /// manual compaction.
+    ///
+    /// Default: true
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::CompactOptions;
+    ///
+    /// let mut options = CompactOptions::default();
+    /// options.set_exclusive_manual_compaction(false);
+    /// ```
+    pub fn set_exclusive_manual_compaction(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_exclusive_manual_compaction(self.inner, v as c_uchar);
+        }
+    }
+
+    /// Sets whether the files of the bottommost level are compacted too.
+    /// Skipping them saves a lot of rewriting when the upper levels hold
+    /// only a small part of the data.
+    ///
+    /// Default: `BottommostLevelCompaction::IfHaveCompactionFilter`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::{BottommostLevelCompaction, CompactOptions};
+    ///
+    /// let mut options = CompactOptions::default();
+    /// options.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+    /// ```
+    pub fn set_bottommost_level_compaction(&mut self, v: BottommostLevelCompaction) {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_bottommost_level_compaction(self.inner, v as c_uchar);
+        }
+    }
+
+    /// If true, the compacted files are moved to the level set by
+    /// `set_target_level` once the compaction is done.
+    ///
+    /// Default: false
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::CompactOptions;
+    ///
+    /// let mut options = CompactOptions::default();
+    /// options.set_change_level(true);
+    /// ```
+    pub fn set_change_level(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_change_level(self.inner, v as c_uchar);
+        }
+    }
+
+    /// Sets the level the compacted files are moved to when
+    /// `set_change_level` is enabled. A negative value means the minimum
+    /// level the data can be moved to without overlapping other files.
+    ///
+    /// Default: -1
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::CompactOptions;
+    ///
+    /// let mut options = CompactOptions::default();
+    /// options.set_target_level(2);
+    /// ```
+    pub fn set_target_level(&mut self, level: c_int) {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_target_level(self.inner, level);
+        }
+    }
+}
+
+impl Default for CompactOptions {
+    fn default() -> CompactOptions {
+        let compact_opts = unsafe { ffi::rocksdb_compactoptions_create() };
+        if compact_opts.is_null() {
+            panic!("Could not create RocksDB compact options");
+        }
+        CompactOptions {
+            inner: compact_opts,
+        }
+    }
+}
+
+impl IngestExternalFileOptions {
+    pub fn new() -> IngestExternalFileOptions {
+        IngestExternalFileOptions::default()
+    }
+
+    /// If true, the files are moved into the database instead of copied.
+    ///
+    /// Default: false
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::IngestExternalFileOptions;
+    ///
+    /// let mut options = IngestExternalFileOptions::default();
+    /// options.set_move_files(true);
+    /// ```
+    pub fn set_move_files(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_set_move_files(self.inner, v as c_uchar);
+        }
+    }
+
+    /// If false, snapshots taken before the ingestion may see the ingested
+    /// keys, which avoids assigning a global sequence number to the files.
+    ///
+    /// Default: true
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::IngestExternalFileOptions;
+    ///
+    /// let mut options = IngestExternalFileOptions::default();
+    /// options.set_snapshot_consistency(false);
+    /// ```
+    pub fn set_snapshot_consistency(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_set_snapshot_consistency(
+                self.inner,
+                v as c_uchar,
+            );
+        }
+    }
+
+    /// If false, ingestion fails when the files overlap keys already in the
+    /// database, instead of assigning them a global sequence number.
+    ///
+    /// Default: true
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::IngestExternalFileOptions;
+    ///
+    /// let mut options = IngestExternalFileOptions::default();
+    /// options.set_allow_global_seqno(false);
+    /// ```
+    pub fn set_allow_global_seqno(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_set_allow_global_seqno(self.inner, v as c_uchar);
+        }
+    }
+
+    /// If false, ingestion fails when the files overlap the memtable,
+    /// instead of flushing it first.
+    ///
+    /// Default: true
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::IngestExternalFileOptions;
+    ///
+    /// let mut options = IngestExternalFileOptions::default();
+    /// options.set_allow_blocking_flush(false);
+    /// ```
+    pub fn set_allow_blocking_flush(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_set_allow_blocking_flush(
+                self.inner,
+                v as c_uchar,
+            );
+        }
+    }
+}
+
+impl Default for IngestExternalFileOptions {
+    fn default() -> IngestExternalFileOptions {
+        let ingest_opts = unsafe { ffi::rocksdb_ingestexternalfileoptions_create() };
+        if ingest_opts.is_null() {
+            panic!("Could not create RocksDB ingest external file options");
+        }
+        IngestExternalFileOptions { inner: ingest_opts }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use MemtableFactory;
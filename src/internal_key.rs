@@ -0,0 +1,164 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use Error;
+
+/// The number of bytes RocksDB appends to a user key to form an internal key.
+const FOOTER_LEN: usize = 8;
+
+/// The kind of entry an internal key belongs to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueType {
+    Deletion = 0x0,
+    Value = 0x1,
+    Merge = 0x2,
+    SingleDeletion = 0x7,
+    RangeDeletion = 0xF,
+    BlobIndex = 0x11,
+}
+
+impl ValueType {
+    fn from_u8(value: u8) -> Option<ValueType> {
+        match value {
+            0x0 => Some(ValueType::Deletion),
+            0x1 => Some(ValueType::Value),
+            0x2 => Some(ValueType::Merge),
+            0x7 => Some(ValueType::SingleDeletion),
+            0xF => Some(ValueType::RangeDeletion),
+            0x11 => Some(ValueType::BlobIndex),
+            _ => None,
+        }
+    }
+}
+
+/// A key as RocksDB stores it internally: the user key, followed by the
+/// sequence number and value type of the entry packed into 8 little-endian
+/// bytes.
+///
+/// Internal keys show up in SST file dumps and in RocksDB's own tooling.
+/// Note that the bundled RocksDB C API does not expose the
+/// `rocksdb.iterator.internal-key` iterator property.
+///
+/// ```
+/// use rocksdb::{InternalKey, ValueType};
+///
+/// let key = InternalKey::new(b"my key", 42, ValueType::Value);
+/// let bytes = key.encode();
+///
+/// let parsed = InternalKey::parse(&bytes).unwrap();
+/// assert_eq!(parsed.user_key(), b"my key");
+/// assert_eq!(parsed.sequence(), 42);
+/// assert_eq!(parsed.value_type(), ValueType::Value);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InternalKey<'a> {
+    user_key: &'a [u8],
+    sequence: u64,
+    value_type: ValueType,
+}
+
+impl<'a> InternalKey<'a> {
+    /// RocksDB only reserves 56 bits for sequence numbers.
+    pub const MAX_SEQUENCE: u64 = (1 << 56) - 1;
+
+    /// Creates an internal key. Panics if `sequence` is larger than
+    /// `MAX_SEQUENCE`.
+    pub fn new(user_key: &'a [u8], sequence: u64, value_type: ValueType) -> InternalKey<'a> {
+        assert!(
+            sequence <= InternalKey::MAX_SEQUENCE,
+            "sequence number out of range"
+        );
+        InternalKey {
+            user_key,
+            sequence,
+            value_type,
+        }
+    }
+
+    /// Parses the bytes of an internal key.
+    pub fn parse(bytes: &'a [u8]) -> Result<InternalKey<'a>, Error> {
+        if bytes.len() < FOOTER_LEN {
+            return Err(Error::new(format!(
+                "Internal key of {} bytes is too short",
+                bytes.len()
+            )));
+        }
+        let (user_key, footer) = bytes.split_at(bytes.len() - FOOTER_LEN);
+        let mut packed = [0; FOOTER_LEN];
+        packed.copy_from_slice(footer);
+        let packed = u64::from_le_bytes(packed);
+
+        let value_type = match ValueType::from_u8(packed as u8) {
+            Some(value_type) => value_type,
+            None => {
+                return Err(Error::new(format!(
+                    "Unknown value type {:#x} in internal key",
+                    packed as u8
+                )));
+            }
+        };
+        Ok(InternalKey {
+            user_key,
+            sequence: packed >> 8,
+            value_type,
+        })
+    }
+
+    pub fn user_key(&self) -> &'a [u8] {
+        self.user_key
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    /// Returns the bytes of this internal key, as understood by `parse`.
+    pub fn encode(&self) -> Vec<u8> {
+        let packed = (self.sequence << 8) | self.value_type as u64;
+        let mut bytes = Vec::with_capacity(self.user_key.len() + FOOTER_LEN);
+        bytes.extend_from_slice(self.user_key);
+        bytes.extend_from_slice(&packed.to_le_bytes());
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_internal_key() {
+        // "k1" written with sequence number 3 as a merge.
+        let bytes = [b'k', b'1', 0x02, 0x03, 0, 0, 0, 0, 0, 0];
+        let key = InternalKey::parse(&bytes).unwrap();
+        assert_eq!(key.user_key(), b"k1");
+        assert_eq!(key.sequence(), 3);
+        assert_eq!(key.value_type(), ValueType::Merge);
+        assert_eq!(key.encode(), bytes.to_vec());
+
+        let key = InternalKey::new(b"", InternalKey::MAX_SEQUENCE, ValueType::Deletion);
+        assert_eq!(InternalKey::parse(&key.encode()).unwrap(), key);
+    }
+
+    #[test]
+    fn parse_invalid_internal_key() {
+        assert!(InternalKey::parse(b"short").is_err());
+        assert!(InternalKey::parse(&[b'k', 0x05, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+}
@@ -62,34 +62,67 @@ mod ffi_util;
 
 pub mod backup;
 pub mod checkpoint;
+mod checksum;
+pub mod codec;
 pub mod compaction_filter;
 mod comparator;
 mod db;
 mod db_options;
+mod internal_key;
 pub mod merge_operator;
+mod metrics;
+pub mod perf;
 mod slice_transform;
+mod sst_file_writer;
 
 pub use compaction_filter::Decision as CompactionDecision;
 pub use db::{
-    DBCompactionStyle, DBCompressionType, DBIterator, DBPinnableSlice, DBRawIterator,
-    DBRecoveryMode, DBVector, Direction, IteratorMode, ReadOptions, Snapshot, WriteBatch,
+    BulkLoader, CappedPrefixIterator, ChangeCursor, DBCompactionStyle, DBCompressionType,
+    DBIterator, DBPinnableSlice, DBRawIterator, DBRecoveryMode, DBVector, Direction, IteratorMode,
+    OperationalSnapshot, ReadOptions, ReadTier, ScanBuilder, ScanIterator, Snapshot, SnapshotId,
+    SnapshotSet, WriteBatch, WriteBatchWithIndex,
 };
 
+pub use internal_key::{InternalKey, ValueType};
+
 pub use slice_transform::SliceTransform;
 
+pub use sst_file_writer::SstFileWriter;
+
 pub use merge_operator::MergeOperands;
+
+pub use metrics::{Metric, MetricKind};
+
 use std::collections::BTreeMap;
 use std::error;
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
 
 /// A RocksDB database.
 ///
 /// See crate level documentation for a simple usage example.
+///
+/// # Thread safety
+///
+/// `DB` is `Send` and `Sync`: all of its methods that take `&self` may be
+/// called concurrently from several threads, so a single instance can be
+/// shared with an `Arc` (see `DB::open_shared`). Snapshots, iterators and
+/// pinned values borrow the database and are neither `Send` nor `Sync`; each
+/// thread should create its own.
 pub struct DB {
     inner: *mut ffi::rocksdb_t,
     cfs: BTreeMap<String, ColumnFamily>,
     path: PathBuf,
+    max_value_size: usize,
+    value_checksum: bool,
+    open_iterators: Arc<db::IteratorLimit>,
+    default_read_opts: RwLock<Arc<ReadOptions>>,
+    default_write_opts: RwLock<Arc<WriteOptions>>,
+    monotonic_prefix_len: usize,
+    last_prefixes: Mutex<BTreeMap<String, Vec<u8>>>,
+    write_observer: RwLock<Option<db::WriteObserver>>,
+    observed_writes: Mutex<()>,
 }
 
 /// A descriptor for a RocksDB column family.
@@ -105,11 +138,42 @@ pub struct ColumnFamilyDescriptor {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Error {
     message: String,
+    kind: ErrorKind,
+}
+
+/// The general category of an `Error`.
+///
+/// Most errors come straight from RocksDB and are reported as `Other`; the
+/// remaining kinds are used by this crate for conditions callers commonly
+/// want to match on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The database lock could not be acquired before the timeout expired.
+    LockTimeout,
+    /// The named column family is not open in this database (it was
+    /// never created, or has been dropped).
+    ColumnFamilyNotFound,
+    /// A value passed to `put` or `merge` exceeds the limit set with
+    /// `Options::set_max_value_size`.
+    ValueTooLarge,
+    /// A value read with `get` does not match the checksum stored with it
+    /// by `Options::set_value_checksum`.
+    ChecksumMismatch,
+    /// Any other error.
+    Other,
 }
 
 impl Error {
     fn new(message: String) -> Error {
-        Error { message }
+        Error::with_kind(ErrorKind::Other, message)
+    }
+
+    fn with_kind(kind: ErrorKind, message: String) -> Error {
+        Error { message, kind }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
     }
 
     pub fn into_string(self) -> String {
@@ -141,6 +205,46 @@ impl fmt::Display for Error {
     }
 }
 
+/// Optional capabilities of the RocksDB library this crate is linked
+/// against, as returned by `features`.
+///
+/// The compression codecs follow the cargo features librocksdb-sys was
+/// built with. When linking a prebuilt RocksDB through `ROCKSDB_LIB_DIR`,
+/// that library is assumed to support the same codecs, and `rtti` is only
+/// reported for the bundled build.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BuildFeatures {
+    pub snappy: bool,
+    pub lz4: bool,
+    pub zstd: bool,
+    pub zlib: bool,
+    pub bzip2: bool,
+    pub jemalloc: bool,
+    pub rtti: bool,
+}
+
+/// Returns the optional capabilities of the linked RocksDB library.
+///
+/// ```
+/// use rocksdb::{DBCompressionType, Options};
+///
+/// let mut opts = Options::default();
+/// if rocksdb::features().zstd {
+///     opts.set_compression_type(DBCompressionType::Zstd);
+/// }
+/// ```
+pub fn features() -> BuildFeatures {
+    BuildFeatures {
+        snappy: cfg!(rocksdb_snappy),
+        lz4: cfg!(rocksdb_lz4),
+        zstd: cfg!(rocksdb_zstd),
+        zlib: cfg!(rocksdb_zlib),
+        bzip2: cfg!(rocksdb_bzip2),
+        jemalloc: cfg!(rocksdb_jemalloc),
+        rtti: cfg!(rocksdb_rtti),
+    }
+}
+
 /// For configuring block-based file storage.
 pub struct BlockBasedOptions {
     inner: *mut ffi::rocksdb_block_based_table_options_t,
@@ -174,6 +278,16 @@ pub enum MemtableFactory {
     },
 }
 
+/// Used by Options::set_access_hint_on_compaction_start to tell the OS how
+/// the input files of a compaction are going to be accessed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AccessHint {
+    None = 0,
+    Normal = 1,
+    Sequential = 2,
+    WillNeed = 3,
+}
+
 /// Used with DBOptions::set_plain_table_factory.
 /// See https://github.com/facebook/rocksdb/wiki/PlainTable-Format.
 ///
@@ -224,6 +338,10 @@ pub struct PlainTableFactoryOptions {
 /// ```
 pub struct Options {
     inner: *mut ffi::rocksdb_options_t,
+    max_value_size: usize,
+    value_checksum: bool,
+    disable_wal: bool,
+    monotonic_prefix_len: usize,
 }
 
 /// Optionally wait for the memtable flush to be performed.
@@ -279,6 +397,83 @@ pub struct WriteOptions {
     inner: *mut ffi::rocksdb_writeoptions_t,
 }
 
+/// Used by CompactOptions::set_bottommost_level_compaction to control
+/// whether a manual compaction also rewrites the files of the bottommost
+/// level.
+///
+/// The bundled RocksDB predates `kForceOptimized`, so there is no variant for
+/// it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BottommostLevelCompaction {
+    /// Skip the bottommost level. Files from upper levels are still merged
+    /// into it, but files that are already there are left untouched, which
+    /// avoids rewriting a large, cold bottom level.
+    Skip = 0,
+    /// Only compact the bottommost level if a compaction filter is set, so
+    /// that the filter gets to see every key. This is the default, and the
+    /// usual choice for periodic maintenance compactions.
+    IfHaveCompactionFilter = 1,
+    /// Always compact the bottommost level, e.g. to rewrite all files with
+    /// new compression or table options.
+    Force = 2,
+}
+
+/// Optionally move the output of a manual compaction to a given level, or
+/// configure how the bottommost level is compacted.
+///
+/// # Examples
+///
+/// Compacting the whole key space into level 2:
+///
+/// ```
+/// use rocksdb::{CompactOptions, Options, DB};
+///
+/// let path = "_path_for_rocksdb_storage_compact_options";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     db.put(b"my key", b"my value").unwrap();
+///
+///     let mut compact_options = CompactOptions::default();
+///     compact_options.set_change_level(true);
+///     compact_options.set_target_level(2);
+///
+///     db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_options);
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct CompactOptions {
+    inner: *mut ffi::rocksdb_compactoptions_t,
+}
+
+/// Configures how `DB::ingest_external_file` adds SST files to a database.
+///
+/// # Examples
+///
+/// Moving the files into the database instead of copying them:
+///
+/// ```
+/// use rocksdb::{IngestExternalFileOptions, Options, SstFileWriter, DB};
+///
+/// let path = "_path_for_rocksdb_storage_ingest_options";
+/// let sst_path = "_path_for_rocksdb_storage_ingest_options.sst";
+/// {
+///     let opts = Options::default();
+///     let mut writer = SstFileWriter::create(&opts);
+///     writer.open(sst_path).unwrap();
+///     writer.put(b"my key", b"my value").unwrap();
+///     writer.finish().unwrap();
+///
+///     let db = DB::open_default(path).unwrap();
+///     let mut ingest_options = IngestExternalFileOptions::default();
+///     ingest_options.set_move_files(true);
+///     db.ingest_external_file_opt(&ingest_options, vec![sst_path]).unwrap();
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct IngestExternalFileOptions {
+    inner: *mut ffi::rocksdb_ingestexternalfileoptions_t,
+}
+
 /// An opaque type used to represent a column family. Returned from some functions, and used
 /// in others
 pub struct ColumnFamily {
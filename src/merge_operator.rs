@@ -57,6 +57,7 @@
 //! ```
 
 use libc::{self, c_char, c_int, c_void, size_t};
+use std::collections::BTreeSet;
 use std::ffi::CString;
 use std::mem;
 use std::ptr;
@@ -145,6 +146,84 @@ pub unsafe extern "C" fn partial_merge_callback(
     }
 }
 
+/// Merge function treating values and operands as 64-bit unsigned integers
+/// (8 bytes, little-endian) and adding them up, like RocksDB's built-in
+/// `UInt64AddOperator`. Values of any other length are treated as zero.
+///
+/// This is the merge function installed by `Options::set_uint64add_merge_operator`.
+pub fn uint64add_merge(
+    _new_key: &[u8],
+    existing_val: Option<&[u8]>,
+    operands: &mut MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut sum = existing_val.map_or(0, decode_u64);
+    for op in operands {
+        sum = sum.wrapping_add(decode_u64(op));
+    }
+    Some(sum.to_le_bytes().to_vec())
+}
+
+fn decode_u64(value: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    if value.len() == bytes.len() {
+        bytes.copy_from_slice(value);
+    }
+    u64::from_le_bytes(bytes)
+}
+
+/// Merge function treating values and operands as sets of byte strings, as
+/// encoded by `encode_sorted_set`, and producing their sorted union without
+/// duplicates. A trailing element that is cut short is ignored.
+///
+/// This is the merge function installed by
+/// `Options::set_sorted_set_union_merge_operator`.
+pub fn sorted_set_union_merge(
+    _new_key: &[u8],
+    existing_val: Option<&[u8]>,
+    operands: &mut MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut union = BTreeSet::new();
+    if let Some(existing) = existing_val {
+        union.extend(decode_sorted_set(existing));
+    }
+    for op in operands {
+        union.extend(decode_sorted_set(op));
+    }
+    Some(encode_sorted_set(union))
+}
+
+/// Encodes set elements for `sorted_set_union_merge`: each element is
+/// prefixed with its length as 4 little-endian bytes.
+pub fn encode_sorted_set<I>(elements: I) -> Vec<u8>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut encoded = Vec::new();
+    for element in elements {
+        let element = element.as_ref();
+        encoded.extend_from_slice(&(element.len() as u32).to_le_bytes());
+        encoded.extend_from_slice(element);
+    }
+    encoded
+}
+
+/// Decodes the elements of a set encoded by `encode_sorted_set`.
+pub fn decode_sorted_set(mut encoded: &[u8]) -> Vec<&[u8]> {
+    let mut elements = Vec::new();
+    while encoded.len() >= 4 {
+        let mut len = [0; 4];
+        len.copy_from_slice(&encoded[..4]);
+        let len = u32::from_le_bytes(len) as usize;
+        if encoded.len() - 4 < len {
+            break;
+        }
+        elements.push(&encoded[4..4 + len]);
+        encoded = &encoded[4 + len..];
+    }
+    elements
+}
+
 pub struct MergeOperands {
     operands_list: *const *const c_char,
     operands_list_len: *const size_t,
@@ -202,6 +281,7 @@ impl<'a> Iterator for &'a mut MergeOperands {
 mod test {
 
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     fn test_provided_merge(
         _new_key: &[u8],
@@ -223,6 +303,35 @@ mod test {
         Some(result)
     }
 
+    #[test]
+    fn sorted_set_union_test() {
+        use {Options, DB};
+
+        let path = "_rust_rocksdb_sorted_set_union_test";
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_sorted_set_union_merge_operator();
+        {
+            let db = DB::open(&opts, path).unwrap();
+            db.put(b"k1", encode_sorted_set(&[b"b", b"d"])).unwrap();
+            db.merge(b"k1", encode_sorted_set(&[b"d", b"a"])).unwrap();
+            db.merge(b"k1", encode_sorted_set(&[b"c", b"a", b"b"]))
+                .unwrap();
+            db.merge(b"k2", encode_sorted_set(&[b"x"])).unwrap();
+            db.merge(b"k2", encode_sorted_set(&[b"x"])).unwrap();
+
+            let value = db.get(b"k1").unwrap().unwrap();
+            let expected = vec![&b"a"[..], b"b", b"c", b"d"];
+            assert_eq!(decode_sorted_set(&value), expected);
+            assert_eq!(&*value, &encode_sorted_set(expected)[..]);
+
+            db.flush().unwrap();
+            let value = db.get(b"k2").unwrap().unwrap();
+            assert_eq!(decode_sorted_set(&value), vec![&b"x"[..]]);
+        }
+        assert!(DB::destroy(&opts, path).is_ok());
+    }
+
     #[test]
     fn mergetest() {
         use {Options, DB};
@@ -447,4 +556,44 @@ mod test {
         }
         assert!(DB::destroy(&opts, path).is_ok());
     }
+
+    static PARTIAL_MERGE_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static PARTIAL_MERGE_OPERANDS: AtomicUsize = AtomicUsize::new(0);
+
+    fn test_recording_partial_merge(
+        new_key: &[u8],
+        existing_val: Option<&[u8]>,
+        operands: &mut MergeOperands,
+    ) -> Option<Vec<u8>> {
+        PARTIAL_MERGE_CALLS.fetch_add(1, Ordering::SeqCst);
+        PARTIAL_MERGE_OPERANDS.fetch_add(operands.size_hint().0, Ordering::SeqCst);
+        test_provided_merge(new_key, existing_val, operands)
+    }
+
+    #[test]
+    fn partial_merge_multi_test() {
+        use {Options, DB};
+
+        let path = "_rust_rocksdb_partial_merge_multi_test";
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_merge_operator(
+            "test operator",
+            test_provided_merge,
+            Some(test_recording_partial_merge),
+        );
+        {
+            let db = DB::open(&opts, path).unwrap();
+            for op in b"abcdefghij".chunks(1) {
+                db.merge(b"k1", op).unwrap();
+            }
+            db.flush().unwrap();
+
+            assert_eq!(PARTIAL_MERGE_CALLS.load(Ordering::SeqCst), 1);
+            assert_eq!(PARTIAL_MERGE_OPERANDS.load(Ordering::SeqCst), 10);
+            let r = db.get(b"k1");
+            assert!(r.unwrap().unwrap().to_utf8().unwrap() == "abcdefghij");
+        }
+        assert!(DB::destroy(&opts, path).is_ok());
+    }
 }
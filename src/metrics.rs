@@ -0,0 +1,168 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+/// Properties reported for every column family, labelled with its name.
+pub const CF_PROPERTIES: &[&str] = &[
+    "rocksdb.estimate-num-keys",
+    "rocksdb.live-sst-files-size",
+    "rocksdb.estimate-pending-compaction-bytes",
+    "rocksdb.cur-size-all-mem-tables",
+    "rocksdb.block-cache-usage",
+];
+
+/// Properties of the whole database.
+pub const DB_PROPERTIES: &[&str] = &[
+    "rocksdb.num-running-compactions",
+    "rocksdb.num-running-flushes",
+    "rocksdb.background-errors",
+];
+
+/// How a `Metric` behaves over time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MetricKind {
+    /// A value that only goes up, e.g. a statistics ticker.
+    Counter,
+    /// A value that can go up and down, e.g. a property.
+    Gauge,
+    /// A percentile of a statistics histogram, labelled with its `quantile`.
+    Summary,
+}
+
+/// A single value collected by `DB::collect_metrics`, in a form that maps
+/// directly to the usual metrics exposition formats.
+///
+/// Names are the RocksDB names with dots and dashes replaced by
+/// underscores, e.g. `rocksdb_block_cache_hit` for the
+/// `rocksdb.block.cache.hit` ticker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+    pub kind: MetricKind,
+}
+
+impl Metric {
+    fn new(name: &str, value: f64, kind: MetricKind) -> Metric {
+        Metric {
+            name: metric_name(name),
+            labels: Vec::new(),
+            value,
+            kind,
+        }
+    }
+
+    fn with_label(mut self, name: &str, value: &str) -> Metric {
+        self.labels.push((name.to_owned(), value.to_owned()));
+        self
+    }
+}
+
+/// Turns the value of one of `CF_PROPERTIES` or `DB_PROPERTIES` into a
+/// gauge, labelled with the column family name if there is one.
+pub fn property_gauge(name: &str, value: u64, cf: Option<&str>) -> Metric {
+    let metric = Metric::new(name, value as f64, MetricKind::Gauge);
+    match cf {
+        Some(cf) => metric.with_label("cf", cf),
+        None => metric,
+    }
+}
+
+fn metric_name(name: &str) -> String {
+    name.replace(|c: char| c == '.' || c == '-', "_")
+}
+
+/// Parses the output of `Options::get_statistics`, which has one line per
+/// ticker (`name COUNT : 1`) or histogram
+/// (`name P50 : 1.0 P95 : 2.0 P99 : 3.0 P100 : 4.0 COUNT : 5 SUM : 6`).
+pub fn parse_statistics(stats: &str) -> Vec<Metric> {
+    let mut metrics = Vec::new();
+    for line in stats.lines() {
+        let mut tokens = line.split_whitespace();
+        let name = match tokens.next() {
+            Some(name) => name,
+            None => continue,
+        };
+        let mut fields = Vec::new();
+        while let (Some(field), Some(":"), Some(value)) =
+            (tokens.next(), tokens.next(), tokens.next())
+        {
+            if let Ok(value) = value.parse::<f64>() {
+                fields.push((field, value));
+            }
+        }
+
+        if let [("COUNT", value)] = fields[..] {
+            metrics.push(Metric::new(name, value, MetricKind::Counter));
+            continue;
+        }
+        for &(field, value) in &fields {
+            let quantile = match field {
+                "P50" => "0.5",
+                "P95" => "0.95",
+                "P99" => "0.99",
+                "P100" => "1",
+                "COUNT" => {
+                    let name = format!("{}_count", name);
+                    metrics.push(Metric::new(&name, value, MetricKind::Counter));
+                    continue;
+                }
+                "SUM" => {
+                    let name = format!("{}_sum", name);
+                    metrics.push(Metric::new(&name, value, MetricKind::Counter));
+                    continue;
+                }
+                _ => continue,
+            };
+            metrics.push(
+                Metric::new(name, value, MetricKind::Summary).with_label("quantile", quantile),
+            );
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_statistics_string() {
+        let stats = "rocksdb.block.cache.hit COUNT : 12\n\
+                     rocksdb.db.get.micros P50 : 1.500000 P95 : 3.000000 P99 : 4.000000 \
+                     P100 : 9.000000 COUNT : 7 SUM : 21\n";
+        let metrics = parse_statistics(stats);
+        assert_eq!(
+            metrics[0],
+            Metric::new("rocksdb.block.cache.hit", 12.0, MetricKind::Counter)
+        );
+        assert_eq!(metrics[0].name, "rocksdb_block_cache_hit");
+        assert_eq!(
+            metrics[1],
+            Metric::new("rocksdb.db.get.micros", 1.5, MetricKind::Summary)
+                .with_label("quantile", "0.5")
+        );
+        assert_eq!(
+            metrics[4].labels,
+            vec![("quantile".to_owned(), "1".to_owned())]
+        );
+        assert_eq!(
+            metrics[5],
+            Metric::new("rocksdb_db_get_micros_count", 7.0, MetricKind::Counter)
+        );
+        assert_eq!(metrics[6].name, "rocksdb_db_get_micros_sum");
+        assert_eq!(metrics.len(), 7);
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Bindings to RocksDB's per-thread performance counters[1].
+//!
+//! [1]: https://github.com/facebook/rocksdb/wiki/Perf-Context-and-IO-Stats-Context
+
+use libc::{self, c_int, c_uchar, c_void};
+use std::ffi::CStr;
+
+use ffi;
+
+/// How much RocksDB measures in the perf context of the current thread.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PerfStatsLevel {
+    /// Unknown setting.
+    Uninitialized = 0,
+    /// Disable perf stats.
+    Disable,
+    /// Enables only count stats.
+    EnableCount,
+    /// Count stats and enable time stats except for mutexes.
+    EnableTimeExceptForMutex,
+    /// Enables all stats, including the time spent waiting on mutexes.
+    EnableTime,
+    /// N.B. Must always be the last value!
+    OutOfBounds,
+}
+
+/// Sets the perf stats level for the current thread.
+pub fn set_perf_stats(level: PerfStatsLevel) {
+    unsafe {
+        ffi::rocksdb_set_perf_level(level as c_int);
+    }
+}
+
+/// The perf counters of the current thread.
+///
+/// ```
+/// use rocksdb::perf::{set_perf_stats, PerfContext, PerfStatsLevel};
+/// use rocksdb::{Options, DB};
+///
+/// let path = "_path_for_rocksdb_storage_perf_context";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     set_perf_stats(PerfStatsLevel::EnableCount);
+///     let mut context = PerfContext::default();
+///     context.reset();
+///     db.get(b"k1").unwrap();
+///     println!("{}", context.report(true));
+///     set_perf_stats(PerfStatsLevel::Disable);
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct PerfContext {
+    inner: *mut ffi::rocksdb_perfcontext_t,
+}
+
+impl PerfContext {
+    /// Resets all counters to zero.
+    pub fn reset(&mut self) {
+        unsafe {
+            ffi::rocksdb_perfcontext_reset(self.inner);
+        }
+    }
+
+    /// Returns a human readable dump of all counters, leaving out those that
+    /// are zero if `exclude_zero_counters` is true.
+    pub fn report(&self, exclude_zero_counters: bool) -> String {
+        unsafe {
+            let ptr = ffi::rocksdb_perfcontext_report(self.inner, exclude_zero_counters as c_uchar);
+            let report = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            libc::free(ptr as *mut c_void);
+            report
+        }
+    }
+}
+
+impl Default for PerfContext {
+    fn default() -> PerfContext {
+        let context = unsafe { ffi::rocksdb_perfcontext_create() };
+        if context.is_null() {
+            panic!("Could not create Perf Context");
+        }
+        PerfContext { inner: context }
+    }
+}
+
+impl Drop for PerfContext {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_perfcontext_destroy(self.inner);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use libc::{c_char, size_t};
+
+use checksum;
+use ffi;
+use {Error, Options};
+
+/// Writes SST files outside of a database, to be added to one later with
+/// `DB::ingest_external_file`.
+///
+/// Keys must be added in ascending order, as defined by the comparator of
+/// the `Options` the writer was created with. If those options enable
+/// `Options::set_value_checksum`, values are written with their checksum, so
+/// that the file can be ingested into a database opened with them.
+///
+/// ```
+/// use rocksdb::{Options, SstFileWriter, DB};
+///
+/// let path = "_path_for_rocksdb_storage_sst_file_writer";
+/// let sst_path = "_path_for_rocksdb_storage_sst_file_writer.sst";
+/// {
+///     let opts = Options::default();
+///     let mut writer = SstFileWriter::create(&opts);
+///     writer.open(sst_path).unwrap();
+///     writer.put(b"k1", b"v1").unwrap();
+///     writer.put(b"k2", b"v2").unwrap();
+///     writer.finish().unwrap();
+///
+///     let db = DB::open_default(path).unwrap();
+///     db.ingest_external_file(vec![sst_path]).unwrap();
+///     assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// let _ = std::fs::remove_file(sst_path);
+/// ```
+pub struct SstFileWriter<'a> {
+    inner: *mut ffi::rocksdb_sstfilewriter_t,
+    // The writer keeps raw pointers to the comparator and merge operator of
+    // the options it was created with.
+    opts: PhantomData<&'a Options>,
+    value_checksum: bool,
+}
+
+impl<'a> SstFileWriter<'a> {
+    pub fn create(opts: &'a Options) -> SstFileWriter<'a> {
+        unsafe {
+            let env_opts = ffi::rocksdb_envoptions_create();
+            let writer = ffi::rocksdb_sstfilewriter_create(env_opts, opts.inner);
+            ffi::rocksdb_envoptions_destroy(env_opts);
+            SstFileWriter {
+                inner: writer,
+                opts: PhantomData,
+                value_checksum: opts.value_checksum,
+            }
+        }
+    }
+
+    /// Creates the SST file at `path`, which must not be in use by a
+    /// database.
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let cpath = match CString::new(path.to_string_lossy().as_bytes()) {
+            Ok(c) => c,
+            Err(_) => {
+                return Err(Error::new(
+                    "Failed to convert path to CString when opening SST file writer.".to_owned(),
+                ));
+            }
+        };
+        unsafe {
+            ffi_try!(ffi::rocksdb_sstfilewriter_open(self.inner, cpath.as_ptr(),));
+        }
+        Ok(())
+    }
+
+    /// Adds a value under the given key.
+    pub fn put<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+        let checked;
+        let value = if self.value_checksum {
+            checked = checksum::append(key, value);
+            &checked[..]
+        } else {
+            value
+        };
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_sstfilewriter_put(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn merge<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+        if self.value_checksum {
+            return Err(Error::new(
+                "Merge is not supported with value checksums enabled".to_owned(),
+            ));
+        }
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_sstfilewriter_merge(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_sstfilewriter_delete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Finalizes the SST file. The file cannot be ingested before this is
+    /// called, and no more entries can be added afterwards.
+    pub fn finish(&mut self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_sstfilewriter_finish(self.inner,));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Drop for SstFileWriter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_sstfilewriter_destroy(self.inner);
+        }
+    }
+}
@@ -16,6 +16,8 @@ extern crate rocksdb;
 mod util;
 
 use rocksdb::{checkpoint::Checkpoint, Options, DB};
+use std::fs;
+use std::path::Path;
 use util::DBPath;
 
 #[test]
@@ -98,3 +100,49 @@ pub fn test_multi_checkpoints() {
     assert_eq!(*cp.get(b"k5").unwrap().unwrap(), *b"v5");
     assert_eq!(*cp.get(b"k6").unwrap().unwrap(), *b"v6");
 }
+
+#[test]
+pub fn test_durability_via_checkpoint() {
+    const PATH_PREFIX: &str = "_rust_rocksdb_cp_durability_";
+
+    fn copy_files(from: &Path, to: &Path) {
+        fs::create_dir_all(to).unwrap();
+        for entry in fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+        }
+    }
+
+    let db_path = DBPath::new(&format!("{}db", PATH_PREFIX));
+    let cp_path = DBPath::new(&format!("{}cp", PATH_PREFIX));
+    let crash_path = DBPath::new(&format!("{}crash", PATH_PREFIX));
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.durability_via_checkpoint();
+        let db = DB::open(&opts, &db_path).unwrap();
+
+        db.put(b"k1", b"v1").unwrap();
+        db.checkpoint_now(&cp_path).unwrap();
+        db.put(b"k2", b"v2").unwrap();
+
+        // Closing the database would flush `k2`, so simulate a crash by
+        // keeping the files as they are on disk while it is still open.
+        copy_files(db_path.as_ref(), crash_path.as_ref());
+    }
+    fs::remove_dir_all(&db_path).unwrap();
+    copy_files(crash_path.as_ref(), db_path.as_ref());
+    {
+        // Nothing was written to the write-ahead log, so the write made
+        // since the checkpoint is lost.
+        let db = DB::open_default(&db_path).unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k2").unwrap().is_none());
+    }
+    {
+        // Recover from the checkpoint.
+        let recovered = DB::open_default(&cp_path).unwrap();
+        assert_eq!(&*recovered.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(recovered.get(b"k2").unwrap().is_none());
+    }
+}
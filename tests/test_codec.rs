@@ -0,0 +1,126 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+mod util;
+
+use rocksdb::codec::{Codec, TypedIterator};
+use rocksdb::{IteratorMode, DB};
+use util::DBPath;
+
+#[derive(Debug, PartialEq)]
+struct Record {
+    id: u32,
+    name: String,
+}
+
+/// Encodes `u32` keys as 4 big-endian bytes, and `Record`s as the id
+/// followed by a length-prefixed name.
+struct RecordCodec;
+
+fn read_u32(bytes: &[u8]) -> Result<u32, String> {
+    if bytes.len() < 4 {
+        return Err(format!("expected 4 bytes, got {}", bytes.len()));
+    }
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[..4]);
+    Ok(u32::from_be_bytes(buf))
+}
+
+impl Codec<u32> for RecordCodec {
+    type Error = String;
+
+    fn encode(&self, value: &u32) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<u32, String> {
+        read_u32(bytes)
+    }
+}
+
+impl Codec<Record> for RecordCodec {
+    type Error = String;
+
+    fn encode(&self, value: &Record) -> Vec<u8> {
+        let mut bytes = value.id.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&(value.name.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(value.name.as_bytes());
+        bytes
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Record, String> {
+        let id = read_u32(bytes)?;
+        let len = read_u32(&bytes[4..])? as usize;
+        let name = &bytes[8..];
+        if name.len() != len {
+            return Err(format!("expected a {} byte name, got {}", len, name.len()));
+        }
+        let name = String::from_utf8(name.to_vec()).map_err(|e| e.to_string())?;
+        Ok(Record { id, name })
+    }
+}
+
+#[test]
+fn test_typed_iterator() {
+    let path = DBPath::new("_rust_rocksdb_test_typed_iterator");
+    {
+        let db = DB::open_default(&path).unwrap();
+        let records = vec![
+            Record {
+                id: 1,
+                name: "one".to_owned(),
+            },
+            Record {
+                id: 2,
+                name: "two".to_owned(),
+            },
+        ];
+        for record in &records {
+            db.put(RecordCodec.encode(&record.id), RecordCodec.encode(record))
+                .unwrap();
+        }
+
+        let iter: TypedIterator<u32, Record, _> =
+            TypedIterator::new(db.iterator(IteratorMode::Start).unwrap(), RecordCodec);
+        let decoded: Vec<(u32, Record)> = iter.collect::<Result<_, _>>().unwrap();
+        let expected: Vec<(u32, Record)> = records.into_iter().map(|r| (r.id, r)).collect();
+        assert_eq!(decoded, expected);
+    }
+}
+
+#[test]
+fn test_typed_iterator_decode_errors() {
+    let path = DBPath::new("_rust_rocksdb_test_typed_iterator_decode_errors");
+    {
+        let db = DB::open_default(&path).unwrap();
+        let record = Record {
+            id: 1,
+            name: "one".to_owned(),
+        };
+        db.put(RecordCodec.encode(&1u32), RecordCodec.encode(&record))
+            .unwrap();
+        db.put(RecordCodec.encode(&2u32), b"garbage").unwrap();
+        db.put(RecordCodec.encode(&3u32), RecordCodec.encode(&record))
+            .unwrap();
+
+        let iter: TypedIterator<u32, Record, _> =
+            TypedIterator::new(db.iterator(IteratorMode::Start).unwrap(), RecordCodec);
+        let results: Vec<_> = iter.collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().0, 3);
+    }
+}
@@ -15,7 +15,9 @@
 extern crate rocksdb;
 mod util;
 
-use rocksdb::{ColumnFamilyDescriptor, MergeOperands, Options, DB};
+use rocksdb::{
+    ColumnFamilyDescriptor, ErrorKind, IteratorMode, MergeOperands, Options, WriteOptions, DB,
+};
 use util::DBPath;
 
 #[test]
@@ -252,3 +254,118 @@ fn test_create_duplicate_column_family() {
         assert!(db.create_cf("cf1", &opts).is_err());
     }
 }
+
+#[test]
+fn test_merge_cf_opt_without_wal() {
+    let n = DBPath::new("_rust_rocksdb_cftest_merge_cf_opt_without_wal");
+    {
+        let mut cf_opts = Options::default();
+        cf_opts.set_merge_operator("test operator", test_provided_merge, None);
+        let cf_descriptor = ColumnFamilyDescriptor::new("cf1", cf_opts);
+
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf_descriptors(&opts, &n, vec![cf_descriptor]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(true);
+        db.put_cf_opt(cf1, b"k1", b"a", &write_opts).unwrap();
+        db.merge_cf_opt(cf1, b"k1", b"b", &write_opts).unwrap();
+        db.merge_cf_opt(cf1, b"k1", b"c", &write_opts).unwrap();
+        assert_eq!(&*db.get_cf(cf1, b"k1").unwrap().unwrap(), b"abc");
+
+        db.delete_cf_opt(cf1, b"k1", &write_opts).unwrap();
+        assert!(db.get_cf(cf1, b"k1").unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_dropped_column_family() {
+    let n = DBPath::new("_rust_rocksdb_cftest_dropped");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let mut db = DB::open_cf(&opts, &n, &["cf1"]).unwrap();
+        assert!(db.cf_exists("cf1"));
+        assert!(!db.cf_exists("cf2"));
+
+        db.drop_cf("cf1").unwrap();
+        assert!(!db.cf_exists("cf1"));
+        assert!(db.cf_handle("cf1").is_none());
+
+        let err = db.drop_cf("cf1").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ColumnFamilyNotFound);
+    }
+}
+
+#[test]
+fn test_snapshot_cf_reads() {
+    let n = DBPath::new("_rust_rocksdb_cftest_snapshot");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &n, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put_cf(cf1, b"k1", b"v1").unwrap();
+
+        let snapshot = db.snapshot();
+        db.put_cf(cf1, b"k1", b"v2").unwrap();
+        db.put_cf(cf1, b"k2", b"v2").unwrap();
+
+        assert_eq!(&*snapshot.get_cf(cf1, b"k1").unwrap().unwrap(), b"v1");
+        assert!(snapshot.get_cf(cf1, b"k2").unwrap().is_none());
+        assert!(snapshot.get(b"k1").unwrap().is_none());
+        assert_eq!(&*db.get_cf(cf1, b"k1").unwrap().unwrap(), b"v2");
+
+        let keys: Vec<_> = snapshot
+            .iterator_cf(cf1, IteratorMode::Start)
+            .unwrap()
+            .map(|(k, v)| (k.to_vec(), v.to_vec()))
+            .collect();
+        assert_eq!(keys, vec![(b"k1".to_vec(), b"v1".to_vec())]);
+    }
+}
+
+#[test]
+fn test_truncate_cf() {
+    let n = DBPath::new("_rust_rocksdb_truncate_cf");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &n, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        let value = vec![b'x'; 1024];
+        for i in 0..1000 {
+            let key = format!("k{:04}", i);
+            db.put_cf(cf1, key.as_bytes(), &value).unwrap();
+            if i % 250 == 249 {
+                db.flush_cf(cf1).unwrap();
+            }
+        }
+        db.put(b"k0000", b"default").unwrap();
+        let sst_size = |db: &DB| {
+            db.property_int_value_cf(cf1, "rocksdb.live-sst-files-size")
+                .unwrap()
+                .unwrap()
+        };
+        let size_before = sst_size(&db);
+        assert!(size_before > 1000 * 1024 / 2);
+
+        db.truncate_cf(cf1).unwrap();
+        assert_eq!(db.iterator_cf(cf1, IteratorMode::Start).unwrap().count(), 0);
+        assert!(sst_size(&db) < size_before / 10);
+        assert_eq!(&*db.get(b"k0000").unwrap().unwrap(), b"default");
+
+        // The handle is still usable.
+        db.put_cf(cf1, b"k0001", b"new").unwrap();
+        assert_eq!(&*db.get_cf(cf1, b"k0001").unwrap().unwrap(), b"new");
+        db.truncate_cf(cf1).unwrap();
+        assert!(db.get_cf(cf1, b"k0001").unwrap().is_none());
+    }
+}
@@ -49,3 +49,34 @@ fn compaction_filter_test() {
         assert_eq!(&*db.get(b"%k").unwrap().unwrap(), b"secret");
     }
 }
+
+#[test]
+fn compaction_filter_removed_count() {
+    let path = DBPath::new("_rust_rocksdb_filter_removed_count");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.enable_statistics();
+    opts.set_compaction_filter("test", test_filter);
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        for i in 0..5 {
+            db.put(format!("_k{}", i), b"expired").unwrap();
+        }
+        for i in 0..3 {
+            db.put(format!("k{}", i), b"live").unwrap();
+        }
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+
+        let stats = opts.get_statistics().unwrap();
+        let removed: u64 = stats
+            .lines()
+            .find(|line| line.starts_with("rocksdb.compaction.key.drop.user COUNT"))
+            .and_then(|line| line.rsplit(' ').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(removed, 5);
+        assert!(db.get(b"_k0").unwrap().is_none());
+        assert_eq!(&*db.get(b"k0").unwrap().unwrap(), b"live");
+    }
+}
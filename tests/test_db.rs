@@ -19,8 +19,15 @@ mod util;
 
 use libc::size_t;
 
-use rocksdb::{DBVector, Error, IteratorMode, Options, Snapshot, WriteBatch, DB};
+use rocksdb::{
+    BlockBasedOptions, BottommostLevelCompaction, ChangeCursor, CompactOptions, DBVector, Error,
+    ErrorKind, FlushOptions, IteratorMode, Options, ReadOptions, ReadTier, Snapshot, SnapshotSet,
+    SstFileWriter, WriteBatch, WriteBatchWithIndex, WriteOptions, DB,
+};
+use std::fs;
+use std::ops::ControlFlow;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{mem, thread};
 use util::DBPath;
 
@@ -138,7 +145,7 @@ fn iterator_test() {
             assert!(db.put(key, value).is_ok());
         }
 
-        let iter = db.iterator(IteratorMode::Start);
+        let iter = db.iterator(IteratorMode::Start).unwrap();
 
         for (idx, (db_key, db_value)) in iter.enumerate() {
             let (key, value) = data[idx];
@@ -251,3 +258,885 @@ fn test_sequence_number() {
         assert_eq!(db.latest_sequence_number(), 1);
     }
 }
+
+#[test]
+fn test_ignore_range_deletions() {
+    let path = DBPath::new("_rust_rocksdb_test_ignore_range_deletions");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+        db.put(b"k3", b"v3").unwrap();
+
+        let mut batch = WriteBatch::default();
+        batch.delete_range(b"k1", b"k3").unwrap();
+        db.write(batch).unwrap();
+
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert!(db.get(b"k2").unwrap().is_none());
+        assert_eq!(db.iterator(IteratorMode::Start).unwrap().count(), 1);
+
+        let mut opts = ReadOptions::default();
+        opts.set_ignore_range_deletions(true);
+        assert_eq!(&*db.get_opt(b"k1", &opts).unwrap().unwrap(), b"v1");
+        assert_eq!(&*db.get_opt(b"k2", &opts).unwrap().unwrap(), b"v2");
+        assert_eq!(
+            db.iterator_opt(IteratorMode::Start, &opts).unwrap().count(),
+            3
+        );
+    }
+}
+
+#[test]
+fn test_try_open_lock_timeout() {
+    let path = DBPath::new("_rust_rocksdb_test_try_open_lock_timeout");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::try_open(&opts, &path, Duration::from_secs(1)).unwrap();
+
+        let err = DB::try_open(&opts, &path, Duration::from_millis(50))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::LockTimeout);
+
+        drop(db);
+        let _db = DB::try_open(&opts, &path, Duration::from_millis(50)).unwrap();
+    }
+}
+
+#[test]
+fn test_close() {
+    let path = DBPath::new("_rust_rocksdb_test_close");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        {
+            let cf1 = db.cf_handle("cf1").unwrap();
+            db.put_cf(cf1, b"k1", b"v1").unwrap();
+            db.put(b"k2", b"v2").unwrap();
+            let snapshot = db.snapshot();
+            assert_eq!(snapshot.iterator(IteratorMode::Start).unwrap().count(), 1);
+        }
+        assert!(db.close().is_ok());
+    }
+    {
+        let db = DB::open_cf(&Options::default(), &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(&*db.get_cf(cf1, b"k1").unwrap().unwrap(), b"v1");
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+    }
+}
+
+#[test]
+fn test_settle() {
+    let path = DBPath::new("_rust_rocksdb_test_settle");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_level_zero_file_num_compaction_trigger(2);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        for i in 0..8 {
+            let key = format!("k{}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+            db.put_cf(cf1, key.as_bytes(), b"value").unwrap();
+            db.flush().unwrap();
+            db.flush_cf(cf1).unwrap();
+        }
+
+        db.settle().unwrap();
+
+        let l0_files = db
+            .property_int_value("rocksdb.num-files-at-level0")
+            .unwrap()
+            .unwrap();
+        assert!(l0_files < 2);
+        let l0_files = db
+            .property_int_value_cf(cf1, "rocksdb.num-files-at-level0")
+            .unwrap()
+            .unwrap();
+        assert!(l0_files < 2);
+        assert_eq!(
+            db.property_int_value("rocksdb.num-running-compactions")
+                .unwrap(),
+            Some(0)
+        );
+    }
+}
+
+fn ticker(opts: &Options, name: &str) -> u64 {
+    let stats = opts.get_statistics().unwrap();
+    let line = stats
+        .lines()
+        .find(|line| line.starts_with(&format!("{} COUNT", name)))
+        .unwrap();
+    line.rsplit(' ').next().unwrap().parse().unwrap()
+}
+
+#[test]
+fn test_warm_cache() {
+    let path = DBPath::new("_rust_rocksdb_test_warm_cache");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.enable_statistics();
+        let db = DB::open(&opts, &path).unwrap();
+        for i in 0..100 {
+            let key = format!("k{:03}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+        }
+        db.flush().unwrap();
+
+        db.warm_cache(Some(b"k000"), None::<&[u8]>).unwrap();
+
+        let misses = ticker(&opts, "rocksdb.block.cache.miss");
+        let hits = ticker(&opts, "rocksdb.block.cache.hit");
+        for i in 0..100 {
+            let key = format!("k{:03}", i);
+            assert_eq!(&*db.get(key.as_bytes()).unwrap().unwrap(), b"value");
+        }
+        assert_eq!(ticker(&opts, "rocksdb.block.cache.miss"), misses);
+        assert!(ticker(&opts, "rocksdb.block.cache.hit") > hits);
+    }
+}
+
+#[test]
+fn test_as_raw_ptr() {
+    let path = DBPath::new("_rust_rocksdb_test_as_raw_ptr");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        unsafe {
+            assert!(!db.as_raw_ptr().is_null());
+            assert!(!cf1.as_raw_ptr().is_null());
+        }
+    }
+}
+
+#[test]
+fn test_from_raw_borrowed() {
+    let path = DBPath::new("_rust_rocksdb_test_from_raw_borrowed");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        {
+            let borrowed = unsafe { DB::from_raw_borrowed(db.as_raw_ptr()) };
+            assert_eq!(&*borrowed.get(b"k1").unwrap().unwrap(), b"v1");
+            borrowed.put(b"k2", b"v2").unwrap();
+        }
+        // Dropping the borrowed wrapper must leave the database open.
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+    }
+}
+
+#[test]
+fn test_consistent_scan() {
+    let path = DBPath::new("_rust_rocksdb_test_consistent_scan");
+    {
+        let db = Arc::new(DB::open_default(&path).unwrap());
+        for i in 0..10 {
+            let key = format!("k{}", i);
+            db.put(key.as_bytes(), b"old").unwrap();
+        }
+
+        let mut wrote = false;
+        let mut seen = Vec::new();
+        let result = db
+            .consistent_scan(Some(b"k2"), Some(b"k8"), |key, value| {
+                if !wrote {
+                    // Overwrite every key and add new ones in between while
+                    // the scan is in progress.
+                    let db = db.clone();
+                    thread::spawn(move || {
+                        for i in 0..10 {
+                            let key = format!("k{}", i);
+                            db.put(key.as_bytes(), b"new").unwrap();
+                            db.put(format!("{}a", key).as_bytes(), b"new").unwrap();
+                        }
+                    })
+                    .join()
+                    .unwrap();
+                    wrote = true;
+                }
+                assert_eq!(value, b"old");
+                seen.push(key.to_vec());
+                ControlFlow::<()>::Continue(())
+            })
+            .unwrap();
+
+        assert!(result.is_none());
+        let expected: Vec<_> = (2..8).map(|i| format!("k{}", i).into_bytes()).collect();
+        assert_eq!(seen, expected);
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"new");
+    }
+}
+
+#[test]
+fn test_preload_filters() {
+    let path = DBPath::new("_rust_rocksdb_test_preload_filters");
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_bloom_filter(10, false);
+    block_opts.set_cache_index_and_filter_blocks(true);
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_block_based_table_factory(&block_opts);
+        let db = DB::open(&opts, &path).unwrap();
+        for file in 0..3 {
+            for i in 0..10 {
+                let key = format!("k{}{}", file, i);
+                db.put(key.as_bytes(), b"value").unwrap();
+            }
+            db.flush().unwrap();
+        }
+    }
+    {
+        let mut opts = Options::default();
+        opts.set_block_based_table_factory(&block_opts);
+        opts.enable_statistics();
+        let db = DB::open(&opts, &path).unwrap();
+        db.preload_filters().unwrap();
+
+        let misses = ticker(&opts, "rocksdb.block.cache.filter.miss");
+        for file in 0..3 {
+            let key = format!("k{}5a", file);
+            assert!(db.get(key.as_bytes()).unwrap().is_none());
+        }
+        assert_eq!(ticker(&opts, "rocksdb.block.cache.filter.miss"), misses);
+    }
+}
+
+#[test]
+fn test_multi_get() {
+    let path = DBPath::new("_rust_rocksdb_test_multi_get");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        for i in 0..10 {
+            let key = format!("k{}", i);
+            let value = format!("v{}", i);
+            db.put(key.as_bytes(), value.as_bytes()).unwrap();
+            db.put_cf(cf1, key.as_bytes(), value.as_bytes()).unwrap();
+        }
+        db.flush().unwrap();
+
+        let to_vecs = |values: Vec<Result<Option<DBVector>, Error>>| -> Vec<Option<Vec<u8>>> {
+            values
+                .into_iter()
+                .map(|v| v.unwrap().map(|v| v.to_vec()))
+                .collect()
+        };
+
+        let sorted = [&b"k1"[..], b"k3", b"k5", b"missing"];
+        let unsorted = [&b"missing"[..], b"k5", b"k1", b"k3"];
+        let expected = vec![
+            Some(b"v1".to_vec()),
+            Some(b"v3".to_vec()),
+            Some(b"v5".to_vec()),
+            None,
+        ];
+
+        assert_eq!(to_vecs(db.multi_get(&sorted)), expected);
+        assert_eq!(to_vecs(db.multi_get_cf(cf1, &sorted)), expected);
+
+        // The same lookups in a different order return the same values, in
+        // the order the keys were given.
+        let from_unsorted = to_vecs(db.multi_get_cf_opt(cf1, &unsorted, &ReadOptions::default()));
+        let reordered: Vec<_> = [2, 3, 1, 0]
+            .iter()
+            .map(|&i| from_unsorted[i].clone())
+            .collect();
+        assert_eq!(reordered, expected);
+    }
+}
+
+#[test]
+fn test_multi_get_merged() {
+    let path = DBPath::new("_rust_rocksdb_test_multi_get_merged");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_uint64add_merge_operator();
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", &10u64.to_le_bytes()).unwrap();
+        db.flush().unwrap();
+        for i in 1..4u64 {
+            db.merge(b"k1", &i.to_le_bytes()).unwrap();
+            db.merge(b"k2", &(i * 100).to_le_bytes()).unwrap();
+        }
+
+        let values: Vec<_> = db
+            .multi_get(&[&b"k1"[..], b"k2", b"k3"])
+            .into_iter()
+            .map(|v| v.unwrap().map(|v| v.to_vec()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                Some(16u64.to_le_bytes().to_vec()),
+                Some(600u64.to_le_bytes().to_vec()),
+                None,
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_flush_all() {
+    let path = DBPath::new("_rust_rocksdb_test_flush_all");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1", "cf2"]).unwrap();
+        db.put(b"k0", b"v0").unwrap();
+        for name in &["cf1", "cf2"] {
+            let cf = db.cf_handle(name).unwrap();
+            db.put_cf(cf, b"k1", b"v1").unwrap();
+        }
+
+        db.flush_all().unwrap();
+
+        assert_eq!(
+            db.property_int_value("rocksdb.num-entries-active-mem-table")
+                .unwrap(),
+            Some(0)
+        );
+        for name in &["cf1", "cf2"] {
+            let cf = db.cf_handle(name).unwrap();
+            assert_eq!(db.num_immutable_memtables(cf).unwrap(), 0);
+            assert_eq!(
+                db.property_int_value_cf(cf, "rocksdb.num-entries-active-mem-table")
+                    .unwrap(),
+                Some(0)
+            );
+            assert_eq!(&*db.get_cf(cf, b"k1").unwrap().unwrap(), b"v1");
+        }
+    }
+}
+
+#[test]
+fn test_delete_batch() {
+    let path = DBPath::new("_rust_rocksdb_test_delete_batch");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        for key in &[b"k1", b"k2", b"k3", b"k4"] {
+            db.put(key, b"value").unwrap();
+            db.put_cf(cf1, key, b"value").unwrap();
+        }
+
+        db.delete_batch(&[b"k1", b"k3"]).unwrap();
+        db.delete_batch_cf(cf1, vec![b"k2".to_vec(), b"k4".to_vec()])
+            .unwrap();
+
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert!(db.get(b"k2").unwrap().is_some());
+        assert!(db.get(b"k3").unwrap().is_none());
+        assert!(db.get(b"k4").unwrap().is_some());
+        assert!(db.get_cf(cf1, b"k1").unwrap().is_some());
+        assert!(db.get_cf(cf1, b"k2").unwrap().is_none());
+        assert!(db.get_cf(cf1, b"k3").unwrap().is_some());
+        assert!(db.get_cf(cf1, b"k4").unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_upsert() {
+    let path = DBPath::new("_rust_rocksdb_test_upsert");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_uint64add_merge_operator();
+        let db = DB::open(&opts, &path).unwrap();
+
+        db.upsert(b"counter", 1).unwrap();
+        db.upsert(b"counter", 10).unwrap();
+        db.flush().unwrap();
+        db.upsert(b"counter", 100).unwrap();
+
+        let value = db.get(b"counter").unwrap().unwrap();
+        assert_eq!(&*value, &111u64.to_le_bytes());
+    }
+}
+
+#[test]
+fn test_upsert_without_merge_operator() {
+    let path = DBPath::new("_rust_rocksdb_test_upsert_without_merge_operator");
+    {
+        let db = DB::open_default(&path).unwrap();
+        assert!(db.upsert(b"counter", 1).is_err());
+    }
+}
+
+#[test]
+fn test_compact_levels() {
+    fn files_at_level(db: &DB, level: usize) -> u64 {
+        let cf = db.cf_handle("cf1").unwrap();
+        let property = format!("rocksdb.num-files-at-level{}", level);
+        db.property_int_value_cf(cf, &property).unwrap().unwrap()
+    }
+
+    let path = DBPath::new("_rust_rocksdb_test_compact_levels");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_disable_auto_compactions(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf = db.cf_handle("cf1").unwrap();
+        for i in 0..3 {
+            let key = format!("k{}", i);
+            db.put_cf(cf, key.as_bytes(), b"value").unwrap();
+            db.flush_cf(cf).unwrap();
+        }
+        assert_eq!(files_at_level(&db, 0), 3);
+
+        assert!(db.compact_levels(cf, 1, 2).is_err());
+        assert!(db.compact_levels(cf, 2, 1).is_err());
+        assert_eq!(files_at_level(&db, 0), 3);
+
+        db.compact_levels(cf, 0, 2).unwrap();
+        assert_eq!(files_at_level(&db, 0), 0);
+        assert_eq!(files_at_level(&db, 1), 0);
+        assert!(files_at_level(&db, 2) > 0);
+        for i in 0..3 {
+            let key = format!("k{}", i);
+            assert_eq!(&*db.get_cf(cf, key.as_bytes()).unwrap().unwrap(), b"value");
+        }
+    }
+}
+
+#[test]
+fn test_change_cursor_resume() {
+    let path = DBPath::new("_rust_rocksdb_test_change_cursor_resume");
+    {
+        let db = DB::open_default(&path).unwrap();
+        for i in 0..4 {
+            let key = format!("k{}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+        }
+        let mut batch = WriteBatch::default();
+        batch.put(b"b1", b"value").unwrap();
+        batch.put(b"b2", b"value").unwrap();
+        batch.delete(b"k0").unwrap();
+        db.write(batch).unwrap();
+        db.put(b"k4", b"value").unwrap();
+
+        let mut sequences = Vec::new();
+        let mut cursor = db.get_updates_since(0).unwrap();
+        for _ in 0..3 {
+            let (sequence, batch) = cursor.next().unwrap().unwrap();
+            assert_eq!(batch.len(), 1);
+            sequences.push(sequence);
+        }
+        let saved = cursor.next_sequence();
+        drop(cursor);
+
+        let mut cursor = ChangeCursor::resume(&db, saved).unwrap();
+        let mut lengths = Vec::new();
+        for change in &mut cursor {
+            let (sequence, batch) = change.unwrap();
+            sequences.push(sequence);
+            lengths.push(batch.len());
+        }
+        assert_eq!(sequences, vec![1, 2, 3, 4, 5, 8]);
+        assert_eq!(lengths, vec![1, 3, 1]);
+        assert_eq!(cursor.next_sequence(), db.latest_sequence_number() + 1);
+
+        let saved = cursor.next_sequence();
+        assert!(ChangeCursor::resume(&db, saved).unwrap().next().is_none());
+        db.put(b"k5", b"value").unwrap();
+        let (sequence, _) = ChangeCursor::resume(&db, saved)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(sequence, saved);
+    }
+}
+
+#[test]
+fn test_flush_with_sequence() {
+    let path = DBPath::new("_rust_rocksdb_test_flush_with_sequence");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"value").unwrap();
+        db.put(b"k2", b"value").unwrap();
+
+        let sequence = db.flush_with_sequence(&FlushOptions::default()).unwrap();
+        assert_eq!(sequence, 2);
+        db.put(b"k3", b"value").unwrap();
+        db.delete(b"k1").unwrap();
+
+        let replayed: Vec<_> = db
+            .get_updates_since(sequence + 1)
+            .unwrap()
+            .map(|change| change.unwrap().0)
+            .collect();
+        assert_eq!(replayed, vec![3, 4]);
+    }
+}
+
+#[test]
+fn test_default_read_and_write_options() {
+    let path = DBPath::new("_rust_rocksdb_test_default_read_and_write_options");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"value").unwrap();
+        db.flush().unwrap();
+    }
+    {
+        let db = DB::open_shared(&Options::default(), &path).unwrap();
+        let mut readopts = ReadOptions::default();
+        readopts.set_read_tier(ReadTier::BlockCache);
+        db.set_default_read_options(readopts);
+        // k1 is only on disk, and the block cache is cold after reopening.
+        assert!(db.get(b"k1").is_err());
+
+        // Another thread sees the new defaults through the shared handle.
+        let shared = db.clone();
+        thread::spawn(move || assert!(shared.get(b"k1").is_err()))
+            .join()
+            .unwrap();
+
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        db.set_default_write_options(writeopts);
+        db.put(b"k2", b"value").unwrap();
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"value");
+        assert_eq!(db.latest_sequence_number(), 2);
+
+        db.set_default_read_options(ReadOptions::default());
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"value");
+    }
+}
+
+#[test]
+fn test_max_value_size() {
+    let path = DBPath::new("_rust_rocksdb_test_max_value_size");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_max_value_size(8);
+        opts.set_uint64add_merge_operator();
+        let db = DB::open(&opts, &path).unwrap();
+
+        db.put(b"k1", b"12345678").unwrap();
+        let err = db.put(b"k2", b"123456789").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueTooLarge);
+        assert!(db.get(b"k2").unwrap().is_none());
+
+        db.merge(b"k3", 1u64.to_le_bytes()).unwrap();
+        let err = db.merge(b"k3", [0; 9]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueTooLarge);
+        assert_eq!(&*db.get(b"k3").unwrap().unwrap(), &1u64.to_le_bytes());
+    }
+}
+
+#[test]
+fn test_value_checksum() {
+    let path = DBPath::new("_rust_rocksdb_test_value_checksum");
+    let sst_dir = DBPath::new("_rust_rocksdb_test_value_checksum_sst");
+    fs::create_dir_all(&sst_dir).unwrap();
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_value_checksum(true);
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"value").unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"value");
+        assert!(db.merge(b"k1", b"operand").is_err());
+
+        assert_eq!(&*db.get_pinned(b"k1").unwrap().unwrap(), b"value");
+        let values: Vec<_> = db
+            .multi_get(&[&b"k1"[..], b"k2"])
+            .into_iter()
+            .map(|v| v.unwrap().map(|v| v.to_vec()))
+            .collect();
+        assert_eq!(values, vec![Some(b"value".to_vec()), None]);
+        let mut iter = db.raw_iterator().unwrap();
+        iter.seek_to_first();
+        assert_eq!(iter.value(), Some(b"value".to_vec()));
+        assert!(iter.status().is_ok());
+        drop(iter);
+
+        let mut batch = WriteBatch::default();
+        batch.put(b"k2", b"value").unwrap();
+        assert!(db.write(batch).is_err());
+        assert!(db.get(b"k2").unwrap().is_none());
+        let mut batch = WriteBatch::default();
+        batch.delete(b"k2").unwrap();
+        db.write(batch).unwrap();
+
+        let mut batch = WriteBatch::with_value_checksum();
+        batch.put(b"k2", b"value").unwrap();
+        batch
+            .put_slice_parts(&[b"k", b"3"], &[b"val", b"ue"])
+            .unwrap();
+        assert!(batch.merge(b"k2", b"operand").is_err());
+        db.write(batch).unwrap();
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"value");
+        assert_eq!(&*db.get(b"k3").unwrap().unwrap(), b"value");
+
+        let mut indexed = WriteBatchWithIndex::with_value_checksum(0, true);
+        indexed.put(b"k4", b"value").unwrap();
+        assert_eq!(
+            &*indexed.get_from_batch(&opts, b"k4").unwrap().unwrap(),
+            b"value"
+        );
+        assert_eq!(
+            &*indexed.get_from_batch_and_db(&db, b"k1").unwrap().unwrap(),
+            b"value"
+        );
+        db.write_wbwi(&indexed).unwrap();
+        assert_eq!(&*db.get(b"k4").unwrap().unwrap(), b"value");
+        db.delete(b"k4").unwrap();
+
+        // So does an `SstFileWriter` created with the same options.
+        let sst_path = sst_dir.as_ref().join("000001.sst");
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&sst_path).unwrap();
+        writer.put(b"k6", b"value").unwrap();
+        writer.finish().unwrap();
+        db.ingest_external_file(vec![&sst_path]).unwrap();
+        assert_eq!(&*db.get(b"k6").unwrap().unwrap(), b"value");
+    }
+    {
+        // Without the option the checksum is visible, so it can be tampered
+        // with.
+        let db = DB::open_default(&path).unwrap();
+        let raw = db.get(b"k1").unwrap().unwrap().to_vec();
+        assert_eq!(raw.len(), b"value".len() + 4);
+        assert_eq!(&raw[..5], b"value");
+
+        // The checksum covers the key, so a value does not verify under
+        // another one.
+        db.put(b"k5", &raw).unwrap();
+
+        let mut corrupted = raw.clone();
+        corrupted[0] = b'V';
+        db.put(b"k1", &corrupted).unwrap();
+        db.flush().unwrap();
+    }
+    {
+        let mut opts = Options::default();
+        opts.set_value_checksum(true);
+        let db = DB::open(&opts, &path).unwrap();
+        let err = db.get(b"k1").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ChecksumMismatch);
+        let err = db.get(b"k5").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ChecksumMismatch);
+        assert!(db.get_pinned(b"k1").is_err());
+        assert!(db.multi_get(&[b"k1"])[0].is_err());
+
+        let mut iter = db.raw_iterator().unwrap();
+        iter.seek_to_first();
+        assert!(iter.value().is_some());
+        assert_eq!(
+            iter.status().unwrap_err().kind(),
+            ErrorKind::ChecksumMismatch
+        );
+    }
+}
+
+#[test]
+fn test_enforce_monotonic_prefix() {
+    let path = DBPath::new("_rust_rocksdb_test_enforce_monotonic_prefix");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_enforce_monotonic_prefix(4);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        db.put(b"0002-b", b"value").unwrap();
+        db.put(b"0002-a", b"value").unwrap();
+        db.put(b"0003-a", b"value").unwrap();
+        assert!(db.put(b"0001-z", b"value").is_err());
+        assert!(db.get(b"0001-z").unwrap().is_none());
+        db.put(b"0003-b", b"value").unwrap();
+
+        // Column families are tracked separately.
+        db.put_cf(cf1, b"0001-a", b"value").unwrap();
+        assert!(db.put_cf(cf1, b"0000-a", b"value").is_err());
+
+        // The default column family is the same whether or not its handle
+        // is given.
+        let default = db.cf_handle("default").unwrap();
+        assert!(db.put_cf(default, b"0002-a", b"value").is_err());
+        db.put_cf(default, b"0004-a", b"value").unwrap();
+        assert!(db.put(b"0003-a", b"value").is_err());
+
+        // A put that fails does not move the last prefix forward.
+        let mut invalid = WriteOptions::default();
+        invalid.set_sync(true);
+        invalid.disable_wal(true);
+        assert!(db.put_opt(b"0009-a", b"value", &invalid).is_err());
+        db.put(b"0005-a", b"value").unwrap();
+    }
+}
+
+#[test]
+fn test_snapshot_set() {
+    let path = DBPath::new("_rust_rocksdb_test_snapshot_set");
+    {
+        let db = DB::open_default(&path).unwrap();
+        let mut snapshots = SnapshotSet::new(&db);
+        assert_eq!(snapshots.oldest_sequence(), None);
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            db.put(b"k1", format!("v{}", i)).unwrap();
+            ids.push(snapshots.create());
+        }
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots.oldest_sequence(), Some(1));
+        assert_eq!(
+            db.property_int_value("rocksdb.num-snapshots").unwrap(),
+            Some(3)
+        );
+
+        let snapshot = snapshots.get(ids[1]).unwrap();
+        assert_eq!(&*snapshot.get(b"k1").unwrap().unwrap(), b"v1");
+
+        assert!(snapshots.release(ids[0]));
+        assert!(!snapshots.release(ids[0]));
+        assert_eq!(snapshots.oldest_sequence(), Some(2));
+        assert_eq!(snapshots.sequence(ids[2]), Some(3));
+
+        snapshots.release_all();
+        assert!(snapshots.is_empty());
+        assert_eq!(snapshots.oldest_sequence(), None);
+        assert_eq!(
+            db.property_int_value("rocksdb.num-snapshots").unwrap(),
+            Some(0)
+        );
+    }
+}
+
+#[test]
+fn test_bottommost_level_compaction() {
+    fn files_at_level(db: &DB, level: usize) -> u64 {
+        let property = format!("rocksdb.num-files-at-level{}", level);
+        db.property_int_value(&property).unwrap().unwrap()
+    }
+
+    fn sst_file_names(path: &DBPath) -> Vec<String> {
+        let mut names: Vec<_> = std::fs::read_dir(path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".sst"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    let path = DBPath::new("_rust_rocksdb_test_bottommost_level_compaction");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_disable_auto_compactions(true);
+        let db = DB::open(&opts, &path).unwrap();
+        for i in 0..3 {
+            let key = format!("k{}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+            db.put(b"k9", b"value").unwrap();
+            db.flush().unwrap();
+        }
+
+        let mut compact_options = CompactOptions::default();
+        compact_options.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+        db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_options);
+        let bottommost = sst_file_names(&path);
+        assert_eq!(bottommost.len(), 1);
+        assert_eq!(files_at_level(&db, 0), 0);
+
+        // New files in level 0. Their keys sort after those of the bottommost
+        // file, since a file they overlap with would be merged with them in
+        // any mode.
+        for i in 0..3 {
+            let key = format!("m{}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+            db.flush().unwrap();
+        }
+        assert_eq!(files_at_level(&db, 0), 3);
+
+        compact_options.set_bottommost_level_compaction(BottommostLevelCompaction::Skip);
+        db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_options);
+        assert_eq!(files_at_level(&db, 0), 0);
+        let skipped = sst_file_names(&path);
+        assert!(bottommost.iter().all(|name| skipped.contains(name)));
+
+        compact_options.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+        db.compact_range_opt(None::<&[u8]>, None::<&[u8]>, &compact_options);
+        let rewritten = sst_file_names(&path);
+        assert!(rewritten.iter().all(|name| !skipped.contains(name)));
+        assert_eq!(files_at_level(&db, 0), 0);
+        assert_eq!(&*db.get(b"k9").unwrap().unwrap(), b"value");
+        assert_eq!(&*db.get(b"m2").unwrap().unwrap(), b"value");
+    }
+}
+
+#[test]
+fn test_file_deletions() {
+    fn sst_files(path: &DBPath) -> usize {
+        std::fs::read_dir(path)
+            .unwrap()
+            .filter(|entry| {
+                let path = entry.as_ref().unwrap().path();
+                path.extension().map_or(false, |ext| ext == "sst")
+            })
+            .count()
+    }
+
+    let path = DBPath::new("_rust_rocksdb_test_file_deletions");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_disable_auto_compactions(true);
+        let db = DB::open(&opts, &path).unwrap();
+
+        db.disable_file_deletions().unwrap();
+        db.disable_file_deletions().unwrap();
+        // Overlapping files, so the compaction cannot just move them.
+        for i in 0..3 {
+            let key = format!("k{}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+            db.put(b"k9", b"value").unwrap();
+            db.flush().unwrap();
+        }
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+        assert_eq!(sst_files(&path), 4);
+
+        // Deletions stay disabled until every disable call is matched.
+        db.enable_file_deletions(false).unwrap();
+        assert_eq!(sst_files(&path), 4);
+        db.enable_file_deletions(false).unwrap();
+        assert_eq!(sst_files(&path), 1);
+
+        db.disable_file_deletions().unwrap();
+        db.disable_file_deletions().unwrap();
+        db.put(b"k0", b"new value").unwrap();
+        db.flush().unwrap();
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+        assert_eq!(sst_files(&path), 3);
+        db.enable_file_deletions(true).unwrap();
+        assert_eq!(sst_files(&path), 1);
+        assert_eq!(&*db.get(b"k0").unwrap().unwrap(), b"new value");
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+mod util;
+
+use rocksdb::{DBCompressionType, Options, DB};
+use util::DBPath;
+
+fn compressed_write_succeeds(name: &str, compression: DBCompressionType) -> bool {
+    let n = DBPath::new(name);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_compression_type(compression);
+    // RocksDB refuses to open a database with a codec it was built without.
+    let db = match DB::open(&opts, &n) {
+        Ok(db) => db,
+        Err(_) => return false,
+    };
+    db.put(b"k1", vec![b'x'; 4096]).unwrap();
+    db.flush().is_ok() && db.get(b"k1").unwrap().map_or(false, |v| v.len() == 4096)
+}
+
+#[test]
+fn test_features_match_compression_support() {
+    let features = rocksdb::features();
+    assert_eq!(
+        features.snappy,
+        compressed_write_succeeds(
+            "_rust_rocksdb_test_features_snappy",
+            DBCompressionType::Snappy
+        )
+    );
+    assert_eq!(
+        features.zstd,
+        compressed_write_succeeds("_rust_rocksdb_test_features_zstd", DBCompressionType::Zstd)
+    );
+    assert!(!features.jemalloc);
+}
@@ -15,7 +15,10 @@
 extern crate rocksdb;
 mod util;
 
-use rocksdb::{Direction, IteratorMode, MemtableFactory, Options, DB};
+use rocksdb::{
+    ColumnFamilyDescriptor, Direction, IteratorMode, MemtableFactory, Options, ReadOptions, DB,
+};
+use std::ops::ControlFlow;
 use util::DBPath;
 
 fn cba(input: &[u8]) -> Box<[u8]> {
@@ -47,64 +50,64 @@ fn test_iterator() {
             (cba(&k3), cba(&v3)),
         ];
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         // Test that it's idempotent
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         // Test it in reverse a few times
         {
-            let iterator1 = db.iterator(IteratorMode::End);
+            let iterator1 = db.iterator(IteratorMode::End).unwrap();
             let mut tmp_vec = iterator1.collect::<Vec<_>>();
             tmp_vec.reverse();
             assert_eq!(tmp_vec, expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::End);
+            let iterator1 = db.iterator(IteratorMode::End).unwrap();
             let mut tmp_vec = iterator1.collect::<Vec<_>>();
             tmp_vec.reverse();
             assert_eq!(tmp_vec, expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::End);
+            let iterator1 = db.iterator(IteratorMode::End).unwrap();
             let mut tmp_vec = iterator1.collect::<Vec<_>>();
             tmp_vec.reverse();
             assert_eq!(tmp_vec, expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::End);
+            let iterator1 = db.iterator(IteratorMode::End).unwrap();
             let mut tmp_vec = iterator1.collect::<Vec<_>>();
             tmp_vec.reverse();
             assert_eq!(tmp_vec, expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::End);
+            let iterator1 = db.iterator(IteratorMode::End).unwrap();
             let mut tmp_vec = iterator1.collect::<Vec<_>>();
             tmp_vec.reverse();
             assert_eq!(tmp_vec, expected);
         }
         // Try it forward again
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
 
-        let old_iterator = db.iterator(IteratorMode::Start);
+        let old_iterator = db.iterator(IteratorMode::Start).unwrap();
         let p = db.put(&*k4, &*v4);
         assert!(p.is_ok());
         let expected2 = vec![
@@ -117,11 +120,13 @@ fn test_iterator() {
             assert_eq!(old_iterator.collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::Start);
+            let iterator1 = db.iterator(IteratorMode::Start).unwrap();
             assert_eq!(iterator1.collect::<Vec<_>>(), expected2);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::From(b"k2", Direction::Forward));
+            let iterator1 = db
+                .iterator(IteratorMode::From(b"k2", Direction::Forward))
+                .unwrap();
             let expected = vec![
                 (cba(&k2), cba(&v2)),
                 (cba(&k3), cba(&v3)),
@@ -130,35 +135,57 @@ fn test_iterator() {
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::From(b"k2", Direction::Reverse));
+            let iterator1 = db
+                .iterator(IteratorMode::From(b"k2", Direction::Reverse))
+                .unwrap();
             let expected = vec![(cba(&k2), cba(&v2)), (cba(&k1), cba(&v1))];
             assert_eq!(iterator1.collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::From(b"zz", Direction::Reverse));
+            let iterator1 = db
+                .iterator(IteratorMode::From(b"zz", Direction::Reverse))
+                .unwrap();
             let expected = vec![(cba(&k4), cba(&v4)), (cba(&k3), cba(&v3))];
             assert_eq!(iterator1.take(2).collect::<Vec<_>>(), expected);
         }
         {
-            let iterator1 = db.iterator(IteratorMode::From(b"k0", Direction::Forward));
+            let iterator1 = db
+                .iterator(IteratorMode::From(b"k0", Direction::Forward))
+                .unwrap();
             assert!(iterator1.valid());
-            let iterator2 = db.iterator(IteratorMode::From(b"k1", Direction::Forward));
+            let iterator2 = db
+                .iterator(IteratorMode::From(b"k1", Direction::Forward))
+                .unwrap();
             assert!(iterator2.valid());
-            let iterator3 = db.iterator(IteratorMode::From(b"k11", Direction::Forward));
+            let iterator3 = db
+                .iterator(IteratorMode::From(b"k11", Direction::Forward))
+                .unwrap();
             assert!(iterator3.valid());
-            let iterator4 = db.iterator(IteratorMode::From(b"k5", Direction::Forward));
+            let iterator4 = db
+                .iterator(IteratorMode::From(b"k5", Direction::Forward))
+                .unwrap();
             assert!(!iterator4.valid());
-            let iterator5 = db.iterator(IteratorMode::From(b"k0", Direction::Reverse));
+            let iterator5 = db
+                .iterator(IteratorMode::From(b"k0", Direction::Reverse))
+                .unwrap();
             assert!(!iterator5.valid());
-            let iterator6 = db.iterator(IteratorMode::From(b"k1", Direction::Reverse));
+            let iterator6 = db
+                .iterator(IteratorMode::From(b"k1", Direction::Reverse))
+                .unwrap();
             assert!(iterator6.valid());
-            let iterator7 = db.iterator(IteratorMode::From(b"k11", Direction::Reverse));
+            let iterator7 = db
+                .iterator(IteratorMode::From(b"k11", Direction::Reverse))
+                .unwrap();
             assert!(iterator7.valid());
-            let iterator8 = db.iterator(IteratorMode::From(b"k5", Direction::Reverse));
+            let iterator8 = db
+                .iterator(IteratorMode::From(b"k5", Direction::Reverse))
+                .unwrap();
             assert!(iterator8.valid());
         }
         {
-            let mut iterator1 = db.iterator(IteratorMode::From(b"k4", Direction::Forward));
+            let mut iterator1 = db
+                .iterator(IteratorMode::From(b"k4", Direction::Forward))
+                .unwrap();
             iterator1.next();
             assert!(iterator1.valid());
             iterator1.next();
@@ -195,13 +222,13 @@ fn test_prefix_iterator() {
 
         {
             let expected = vec![(cba(&a1), cba(&a1)), (cba(&a2), cba(&a2))];
-            let a_iterator = db.prefix_iterator(b"aaa");
+            let a_iterator = db.prefix_iterator(b"aaa").unwrap();
             assert_eq!(a_iterator.collect::<Vec<_>>(), expected)
         }
 
         {
             let expected = vec![(cba(&b1), cba(&b1)), (cba(&b2), cba(&b2))];
-            let b_iterator = db.prefix_iterator(b"bbb");
+            let b_iterator = db.prefix_iterator(b"bbb").unwrap();
             assert_eq!(b_iterator.collect::<Vec<_>>(), expected)
         }
     }
@@ -245,6 +272,7 @@ fn test_prefix_iterator_uses_full_prefix() {
         let prefix = [0, 1, 1];
         let results: Vec<_> = db
             .prefix_iterator(&prefix)
+            .unwrap()
             .map(|(_, v)| std::str::from_utf8(&v).unwrap().to_string())
             .collect();
 
@@ -283,7 +311,7 @@ fn test_full_iterator() {
 
         // A normal iterator won't work here since we're using a HashSkipList for our memtable
         // implementation (which buckets keys based on their prefix):
-        let bad_iterator = db.iterator(IteratorMode::Start);
+        let bad_iterator = db.iterator(IteratorMode::Start).unwrap();
         assert_eq!(bad_iterator.collect::<Vec<_>>(), vec![]);
 
         let expected = vec![
@@ -293,9 +321,231 @@ fn test_full_iterator() {
             (cba(&b2), cba(&b2)),
         ];
 
-        let a_iterator = db.full_iterator(IteratorMode::Start);
+        let a_iterator = db.full_iterator(IteratorMode::Start).unwrap();
         assert_eq!(a_iterator.collect::<Vec<_>>(), expected)
     }
     let opts = Options::default();
     assert!(DB::destroy(&opts, path).is_ok());
 }
+
+#[test]
+fn test_full_iterator_cf() {
+    let path = DBPath::new("_rust_rocksdb_fulliteratortest_cf");
+    {
+        let mut cf_opts = Options::default();
+        cf_opts.set_prefix_extractor(rocksdb::SliceTransform::create_fixed_prefix(3));
+        cf_opts.set_allow_concurrent_memtable_write(false);
+        cf_opts.set_memtable_factory(MemtableFactory::HashSkipList {
+            bucket_count: 1_000_000,
+            height: 4,
+            branching_factor: 4,
+        });
+        let cf = ColumnFamilyDescriptor::new("cf1", cf_opts);
+
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_allow_concurrent_memtable_write(false);
+
+        let db = DB::open_cf_descriptors(&opts, &path, vec![cf]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        let keys: Vec<&[u8]> = vec![b"aaa1", b"aaa2", b"bbb1", b"bbb2"];
+        for key in &keys {
+            db.put_cf(cf1, key, key).unwrap();
+        }
+
+        // As with `test_full_iterator`, the hash-based memtable hides keys from
+        // a plain iterator that isn't confined to a single prefix.
+        let bad_iterator = db.iterator_cf(cf1, IteratorMode::Start).unwrap();
+        assert!(bad_iterator.count() < keys.len());
+
+        let expected: Vec<_> = keys.iter().map(|k| (cba(k), cba(k))).collect();
+        let full_iterator = db.full_iterator_cf(cf1, IteratorMode::Start).unwrap();
+        assert_eq!(full_iterator.collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn test_scan_builder() {
+    let path = DBPath::new("_rust_rocksdb_scanbuildertest");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_prefix_extractor(rocksdb::SliceTransform::create_fixed_prefix(3));
+        let db = DB::open(&opts, &path).unwrap();
+
+        for key in &[b"aaa1", b"aaa2", b"bbb1", b"bbb2"] {
+            db.put(key, key).unwrap();
+        }
+        db.flush().unwrap();
+        let snapshot = db.snapshot();
+        db.put(b"aaa3", b"aaa3").unwrap();
+        db.delete(b"aaa1").unwrap();
+
+        let expected = vec![(cba(b"aaa1"), cba(b"aaa1")), (cba(b"aaa2"), cba(b"aaa2"))];
+        let iter = db
+            .scan()
+            .prefix_same_as_start(true)
+            .fill_cache(false)
+            .snapshot(&snapshot)
+            .iterator(IteratorMode::From(b"aaa", Direction::Forward))
+            .unwrap();
+        assert_eq!(iter.collect::<Vec<_>>(), expected);
+
+        // Without the snapshot, the later writes are visible.
+        let expected = vec![(cba(b"aaa2"), cba(b"aaa2")), (cba(b"aaa3"), cba(b"aaa3"))];
+        let iter = db
+            .scan()
+            .lower_bound(b"aaa")
+            .upper_bound(b"bbb")
+            .total_order_seek(true)
+            .iterator(IteratorMode::Start)
+            .unwrap();
+        assert_eq!(iter.collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn test_iterator_seek_for_prev() {
+    let path = DBPath::new("_rust_rocksdb_iteratortest_seek_for_prev");
+    {
+        let db = DB::open_default(&path).unwrap();
+        for i in (0..20).step_by(2) {
+            let key = format!("{:04}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+        }
+
+        for &(target, expected) in &[(3, 2), (7, 6), (19, 18), (21, 18)] {
+            let key = format!("{:04}", target);
+            let mut iter = db
+                .iterator(IteratorMode::From(key.as_bytes(), Direction::Reverse))
+                .unwrap();
+            let expected = format!("{:04}", expected);
+            assert_eq!(&*iter.next().unwrap().0, expected.as_bytes());
+        }
+
+        // An existing key is returned itself.
+        let mut iter = db
+            .iterator(IteratorMode::From(b"0010", Direction::Reverse))
+            .unwrap();
+        assert_eq!(&*iter.next().unwrap().0, b"0010");
+        assert_eq!(&*iter.next().unwrap().0, b"0008");
+
+        // Nothing is at or before a key smaller than all keys.
+        let mut iter = db
+            .iterator(IteratorMode::From(b"", Direction::Reverse))
+            .unwrap();
+        assert!(iter.next().is_none());
+    }
+}
+
+#[test]
+fn test_max_open_iterators() {
+    let path = DBPath::new("_rust_rocksdb_iteratortest_max_open_iterators");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.set_max_open_iterators(2);
+
+        let first = db.iterator(IteratorMode::Start).unwrap();
+        let mut second = db.raw_iterator().unwrap();
+        assert!(db.iterator(IteratorMode::Start).is_err());
+        assert!(db.raw_iterator().is_err());
+        assert!(db.full_iterator(IteratorMode::Start).is_err());
+        assert!(db.prefix_iterator(b"k").is_err());
+        let snapshot = db.snapshot();
+        assert!(snapshot.iterator(IteratorMode::Start).is_err());
+        assert!(snapshot.raw_iterator().is_err());
+
+        drop(first);
+        let mut third = db.iterator(IteratorMode::Start).unwrap();
+        assert_eq!(&*third.next().unwrap().0, b"k1");
+        assert!(db.iterator(IteratorMode::Start).is_err());
+
+        // So do the helpers that open iterators internally.
+        let scanned =
+            db.consistent_scan(None::<&[u8]>, None::<&[u8]>, |_, _| ControlFlow::Break(()));
+        assert!(scanned.is_err());
+        assert!(db.warm_cache(None::<&[u8]>, None::<&[u8]>).is_err());
+        assert!(db.prefix_iterator_capped(b"k", 10).is_err());
+        assert!(db.scan().iterator(IteratorMode::Start).is_err());
+
+        second.seek_to_first();
+        assert!(second.valid());
+        drop(second);
+        drop(third);
+
+        db.set_max_open_iterators(0);
+        let _iters: Vec<_> = (0..4)
+            .map(|_| db.iterator(IteratorMode::Start).unwrap())
+            .collect();
+    }
+}
+
+#[test]
+fn test_max_skippable_internal_keys() {
+    let path = DBPath::new("_rust_rocksdb_iteratortest_max_skippable_internal_keys");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"a", b"value").unwrap();
+        for i in 0..100 {
+            let key = format!("b{:03}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+            db.delete(key.as_bytes()).unwrap();
+        }
+        db.put(b"c", b"value").unwrap();
+
+        let mut opts = ReadOptions::default();
+        opts.set_max_skippable_internal_keys(10);
+        let mut iter = db.raw_iterator_opt(&opts).unwrap();
+        iter.seek_to_first();
+        assert_eq!(iter.key(), Some(b"a".to_vec()));
+        iter.next();
+        assert!(!iter.valid());
+        let err = iter.status().unwrap_err();
+        assert!(err.to_string().contains("incomplete"), "{}", err);
+
+        // Without a limit the tombstones are skipped.
+        let keys: Vec<_> = db
+            .iterator(IteratorMode::Start)
+            .unwrap()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec![cba(b"a"), cba(b"c")]);
+    }
+}
+
+#[test]
+fn test_prefix_iterator_capped() {
+    let path = DBPath::new("_rust_rocksdb_iteratortest_prefix_iterator_capped");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        for i in 0..10 {
+            db.put(format!("a{}", i), b"value").unwrap();
+            db.put(format!("b{}", i), b"value").unwrap();
+            db.put_cf(cf1, format!("b{}", i), b"value").unwrap();
+        }
+
+        let mut iter = db.prefix_iterator_capped(b"a", 3).unwrap();
+        let keys: Vec<_> = iter.by_ref().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![cba(b"a0"), cba(b"a1"), cba(b"a2")]);
+        assert!(iter.is_truncated());
+        assert!(iter.status().is_ok());
+
+        let mut iter = db.prefix_iterator_capped(b"a", 10).unwrap();
+        assert_eq!(iter.by_ref().count(), 10);
+        assert!(!iter.is_truncated());
+
+        let mut iter = db.prefix_iterator_capped_cf(cf1, b"b", 5).unwrap();
+        assert_eq!(iter.by_ref().count(), 5);
+        assert!(iter.is_truncated());
+        let mut iter = db.prefix_iterator_capped_cf(cf1, b"a", 5).unwrap();
+        assert!(iter.next().is_none());
+        assert!(!iter.is_truncated());
+    }
+}
@@ -15,7 +15,7 @@
 extern crate rocksdb;
 mod util;
 
-use rocksdb::DB;
+use rocksdb::{Options, DB};
 use std::sync::Arc;
 use std::thread;
 use util::DBPath;
@@ -66,3 +66,37 @@ pub fn test_multithreaded() {
         j3.join().unwrap();
     }
 }
+
+#[test]
+pub fn test_open_shared() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DB>();
+
+    let n = DBPath::new("_rust_rocksdb_open_shared");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open_shared(&opts, &n).unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let db = db.clone();
+                thread::spawn(move || {
+                    for i in 0..1000 {
+                        let key = format!("t{}-k{}", t, i);
+                        db.put(key.as_bytes(), key.as_bytes()).unwrap();
+                        assert_eq!(&*db.get(key.as_bytes()).unwrap().unwrap(), key.as_bytes());
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for t in 0..4 {
+            let key = format!("t{}-k999", t);
+            assert!(db.get(key.as_bytes()).unwrap().is_some());
+        }
+    }
+}
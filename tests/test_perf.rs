@@ -0,0 +1,43 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+mod util;
+
+use rocksdb::perf::{set_perf_stats, PerfContext, PerfStatsLevel};
+use rocksdb::DB;
+use util::DBPath;
+
+#[test]
+fn test_perf_context_report() {
+    let path = DBPath::new("_rust_rocksdb_test_perf_context_report");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+
+        set_perf_stats(PerfStatsLevel::EnableCount);
+        let mut context = PerfContext::default();
+        context.reset();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+
+        let report = context.report(true);
+        assert!(report.contains("get_from_memtable_count"), "{}", report);
+        assert!(!context.report(false).is_empty());
+
+        context.reset();
+        let report = context.report(true);
+        assert!(!report.contains("get_from_memtable_count"), "{}", report);
+        set_perf_stats(PerfStatsLevel::Disable);
+    }
+}
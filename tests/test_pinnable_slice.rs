@@ -24,3 +24,101 @@ fn test_pinnable_slice() {
 
     assert_eq!(b"12345", &pinnable_slice[5..10]);
 }
+
+#[test]
+fn test_get_value_size() {
+    let path = DBPath::new("_rust_rocksdb_get_value_size_test");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    let value = vec![b'x'; 4096];
+    db.put(b"k1", &value).unwrap();
+    db.put_cf(cf1, b"k1", b"value").unwrap();
+    db.flush().unwrap();
+
+    assert_eq!(db.get_value_size(b"k1").unwrap(), Some(value.len()));
+    assert_eq!(db.get_value_size_cf(cf1, b"k1").unwrap(), Some(5));
+    assert_eq!(db.get_value_size(b"k2").unwrap(), None);
+}
+
+#[test]
+fn test_get_value_size_with_checksum() {
+    let path = DBPath::new("_rust_rocksdb_get_value_size_checksum_test");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_value_checksum(true);
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    db.put(b"k1", b"value").unwrap();
+    db.put_cf(cf1, b"k1", b"").unwrap();
+
+    // The checksum stored after the value is not counted.
+    assert_eq!(db.get_value_size(b"k1").unwrap(), Some(5));
+    assert_eq!(db.get_value_size_cf(cf1, b"k1").unwrap(), Some(0));
+    assert_eq!(db.get_value_size(b"k2").unwrap(), None);
+}
+
+#[test]
+fn test_key_exists() {
+    let path = DBPath::new("_rust_rocksdb_key_exists_test");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    db.put(b"k1", b"value").unwrap();
+    db.put(b"k2", b"").unwrap();
+    db.put_cf(cf1, b"k3", b"value").unwrap();
+    db.put(b"k4", b"value").unwrap();
+    db.delete(b"k4").unwrap();
+
+    assert!(db.key_exists(b"k1").unwrap());
+    assert!(db.key_exists(b"k2").unwrap());
+    assert!(!db.key_exists(b"k3").unwrap());
+    assert!(!db.key_exists(b"k4").unwrap());
+    assert!(db.key_exists_cf(cf1, b"k3").unwrap());
+    assert!(!db.key_exists_cf(cf1, b"k1").unwrap());
+
+    db.flush().unwrap();
+    assert!(db.key_exists(b"k1").unwrap());
+    assert!(!db.key_exists(b"k4").unwrap());
+}
+
+#[test]
+fn test_get_into() {
+    let path = DBPath::new("_rust_rocksdb_get_into_test");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    let long = vec![b'x'; 4096];
+    db.put(b"long", &long).unwrap();
+    db.put(b"short", b"abc").unwrap();
+    db.put_cf(cf1, b"k1", b"value").unwrap();
+
+    let mut buf = Vec::new();
+    assert_eq!(db.get_into(b"long", &mut buf).unwrap(), Some(long.len()));
+    assert_eq!(buf, long);
+    let capacity = buf.capacity();
+
+    assert_eq!(db.get_into(b"short", &mut buf).unwrap(), Some(3));
+    assert_eq!(buf, b"abc");
+    assert_eq!(db.get_into_cf(cf1, b"k1", &mut buf).unwrap(), Some(5));
+    assert_eq!(buf, b"value");
+    assert_eq!(buf.capacity(), capacity);
+
+    assert_eq!(db.get_into(b"missing", &mut buf).unwrap(), None);
+    assert!(buf.is_empty());
+}
@@ -15,7 +15,7 @@
 extern crate rocksdb;
 mod util;
 
-use rocksdb::{Options, DB};
+use rocksdb::{MetricKind, Options, DB};
 use util::DBPath;
 
 #[test]
@@ -71,3 +71,81 @@ fn property_int_cf_test() {
         assert_eq!(total_keys, Some(0));
     }
 }
+
+#[test]
+fn property_typed_accessors_test() {
+    let n = DBPath::new("_rust_rocksdb_property_typed_accessors_test");
+    {
+        let opts = Options::default();
+        let mut db = DB::open_default(&n).unwrap();
+        db.create_cf("cf1", &opts).unwrap();
+        let cf = db.cf_handle("cf1").unwrap();
+
+        db.put_cf(cf, b"k1", b"v1").unwrap();
+        assert_eq!(db.num_immutable_memtables(cf).unwrap(), 0);
+
+        db.flush_cf(cf).unwrap();
+        assert_eq!(db.running_flushes().unwrap(), 0);
+        assert_eq!(db.num_immutable_memtables(cf).unwrap(), 0);
+        assert_eq!(db.running_compactions().unwrap(), 0);
+        assert_eq!(db.pending_compaction_bytes().unwrap(), 0);
+    }
+}
+
+#[test]
+fn operational_snapshot_test() {
+    let n = DBPath::new("_rust_rocksdb_operational_snapshot_test");
+    {
+        let db = DB::open_default(&n).unwrap();
+        for i in 0..100 {
+            let key = format!("k{:03}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+        }
+        db.flush().unwrap();
+
+        let snapshot = db.operational_snapshot().unwrap();
+        assert_eq!(snapshot.estimate_num_keys, 100);
+        assert!(snapshot.live_sst_size > 0);
+        assert_eq!(snapshot.pending_compaction_bytes, 0);
+        assert_eq!(snapshot.running_compactions, 0);
+        assert_eq!(snapshot.running_flushes, 0);
+        assert_eq!(snapshot.files_per_level.len(), 7);
+        assert_eq!(snapshot.files_per_level[0], 1);
+        assert!(snapshot.block_cache_usage.is_some());
+        assert_eq!(snapshot.background_errors, 0);
+    }
+}
+
+#[test]
+fn collect_metrics_test() {
+    let n = DBPath::new("_rust_rocksdb_collect_metrics_test");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.enable_statistics();
+        let db = DB::open(&opts, &n).unwrap();
+        for i in 0..10 {
+            db.put(format!("k{}", i), b"value").unwrap();
+        }
+        db.flush().unwrap();
+        for i in 0..10 {
+            db.get(format!("k{}", i)).unwrap().unwrap();
+        }
+
+        let metrics = db.collect_metrics(&opts).unwrap();
+        let hits = metrics
+            .iter()
+            .find(|m| m.name == "rocksdb_block_cache_hit")
+            .unwrap();
+        assert_eq!(hits.kind, MetricKind::Counter);
+        assert!(hits.value > 0.0);
+
+        let keys = metrics
+            .iter()
+            .find(|m| m.name == "rocksdb_estimate_num_keys")
+            .unwrap();
+        assert_eq!(keys.kind, MetricKind::Gauge);
+        assert_eq!(keys.labels, vec![("cf".to_owned(), "default".to_owned())]);
+        assert_eq!(keys.value, 10.0);
+    }
+}
@@ -28,7 +28,7 @@ pub fn test_forwards_iteration() {
         db.put(b"k3", b"v3").unwrap();
         db.put(b"k4", b"v4").unwrap();
 
-        let mut iter = db.raw_iterator();
+        let mut iter = db.raw_iterator().unwrap();
         iter.seek_to_first();
 
         assert_eq!(iter.valid(), true);
@@ -61,7 +61,7 @@ pub fn test_seek_last() {
         db.put(b"k3", b"v3").unwrap();
         db.put(b"k4", b"v4").unwrap();
 
-        let mut iter = db.raw_iterator();
+        let mut iter = db.raw_iterator().unwrap();
         iter.seek_to_last();
 
         assert_eq!(iter.valid(), true);
@@ -93,7 +93,7 @@ pub fn test_seek() {
         db.put(b"k2", b"v2").unwrap();
         db.put(b"k4", b"v4").unwrap();
 
-        let mut iter = db.raw_iterator();
+        let mut iter = db.raw_iterator().unwrap();
         iter.seek(b"k2");
 
         assert_eq!(iter.valid(), true);
@@ -118,7 +118,7 @@ pub fn test_seek_to_nonexistant() {
         db.put(b"k3", b"v3").unwrap();
         db.put(b"k4", b"v4").unwrap();
 
-        let mut iter = db.raw_iterator();
+        let mut iter = db.raw_iterator().unwrap();
         iter.seek(b"k2");
 
         assert_eq!(iter.valid(), true);
@@ -136,7 +136,7 @@ pub fn test_seek_for_prev() {
         db.put(b"k2", b"v2").unwrap();
         db.put(b"k4", b"v4").unwrap();
 
-        let mut iter = db.raw_iterator();
+        let mut iter = db.raw_iterator().unwrap();
         iter.seek(b"k2");
 
         assert_eq!(iter.valid(), true);
@@ -15,9 +15,21 @@
 extern crate rocksdb;
 mod util;
 
-use rocksdb::{Options, DB};
+use rocksdb::{AccessHint, Options, DB};
+use std::fs;
+use std::path::{Path, PathBuf};
 use util::DBPath;
 
+fn files_with_extension(dir: &Path, extension: &str) -> usize {
+    fs::read_dir(dir)
+        .unwrap()
+        .filter(|entry| {
+            let path = entry.as_ref().unwrap().path();
+            path.extension().map_or(false, |ext| ext == extension)
+        })
+        .count()
+}
+
 #[test]
 fn test_set_num_levels() {
     let n = DBPath::new("_rust_rocksdb_test_set_num_levels");
@@ -39,3 +51,224 @@ fn test_increase_parallelism() {
         let _db = DB::open(&opts, &n).unwrap();
     }
 }
+
+#[test]
+fn test_skip_stats_update_on_db_open() {
+    let n = DBPath::new("_rust_rocksdb_test_skip_stats_update_on_db_open");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, &n).unwrap();
+        for i in 0..4 {
+            let key = format!("k{}", i);
+            db.put(key.as_bytes(), b"value").unwrap();
+            db.flush().unwrap();
+        }
+    }
+    {
+        let mut opts = Options::default();
+        opts.set_skip_stats_update_on_db_open(true);
+        let db = DB::open(&opts, &n).unwrap();
+        for i in 0..4 {
+            let key = format!("k{}", i);
+            assert_eq!(&*db.get(key.as_bytes()).unwrap().unwrap(), b"value");
+        }
+    }
+}
+
+#[test]
+fn test_set_wal_dir_and_db_paths() {
+    let n = DBPath::new("_rust_rocksdb_test_set_wal_dir_and_db_paths");
+    let wal_dir = DBPath::new("_rust_rocksdb_test_set_wal_dir_and_db_paths_wal");
+    let sst_dir = DBPath::new("_rust_rocksdb_test_set_wal_dir_and_db_paths_sst");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_wal_dir(&wal_dir);
+        opts.set_db_paths(&[(PathBuf::from(sst_dir.as_ref()), 1 << 30)]);
+        let db = DB::open(&opts, &n).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+
+        assert!(files_with_extension(wal_dir.as_ref(), "log") > 0);
+        assert_eq!(files_with_extension(n.as_ref(), "log"), 0);
+        assert!(files_with_extension(sst_dir.as_ref(), "sst") > 0);
+        assert_eq!(files_with_extension(n.as_ref(), "sst"), 0);
+    }
+}
+
+#[test]
+fn test_set_db_log_dir() {
+    let n = DBPath::new("_rust_rocksdb_test_set_db_log_dir");
+    let log_dir = DBPath::new("_rust_rocksdb_test_set_db_log_dir_log");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_db_log_dir(&log_dir);
+        let db = DB::open(&opts, &n).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+
+        let log_files = fs::read_dir(&log_dir)
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with("LOG")
+            })
+            .count();
+        assert!(log_files > 0);
+        assert!(!Path::new(n.as_ref()).join("LOG").exists());
+    }
+}
+
+#[test]
+fn test_set_bloom_locality() {
+    let n = DBPath::new("_rust_rocksdb_test_set_bloom_locality");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_bloom_locality(1);
+        let db = DB::open(&opts, &n).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k2").unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_set_access_hint_on_compaction_start() {
+    let hints = [
+        AccessHint::None,
+        AccessHint::Normal,
+        AccessHint::Sequential,
+        AccessHint::WillNeed,
+    ];
+    for hint in hints.iter() {
+        let n = DBPath::new("_rust_rocksdb_test_set_access_hint_on_compaction_start");
+        {
+            let mut opts = Options::default();
+            opts.create_if_missing(true);
+            opts.set_access_hint_on_compaction_start(*hint);
+            let db = DB::open(&opts, &n).unwrap();
+            db.put(b"k1", b"v1").unwrap();
+            db.flush().unwrap();
+            db.compact_range(None::<&[u8]>, None::<&[u8]>);
+            assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        }
+    }
+}
+
+#[test]
+fn test_set_log_rotation_options() {
+    let n = DBPath::new("_rust_rocksdb_test_set_log_rotation_options");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_max_log_file_size(1024);
+        opts.set_log_file_time_to_roll(60);
+        opts.set_keep_log_file_num(2);
+        opts.set_recycle_log_file_num(2);
+        let db = DB::open(&opts, &n).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+    }
+}
+
+#[test]
+fn test_set_sync_options() {
+    let n = DBPath::new("_rust_rocksdb_test_set_sync_options");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_use_fsync(true);
+        opts.set_bytes_per_sync(4096);
+        opts.set_wal_bytes_per_sync(4096);
+        opts.set_write_buffer_size(64 * 1024);
+        let db = DB::open(&opts, &n).unwrap();
+        let value = vec![b'x'; 1024];
+        for i in 0..2000 {
+            let key = format!("k{:05}", i);
+            db.put(key.as_bytes(), &value).unwrap();
+        }
+        db.flush().unwrap();
+        assert_eq!(&*db.get(b"k01999").unwrap().unwrap(), &value[..]);
+    }
+}
+
+#[test]
+fn test_set_level_compaction_dynamic_level_bytes() {
+    let n = DBPath::new("_rust_rocksdb_test_set_level_compaction_dynamic_level_bytes");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_level_compaction_dynamic_level_bytes(true);
+        opts.set_write_buffer_size(64 * 1024);
+        opts.set_target_file_size_base(64 * 1024);
+        opts.set_max_bytes_for_level_base(256 * 1024);
+        let db = DB::open(&opts, &n).unwrap();
+        let value = vec![b'x'; 1024];
+        for i in 0..4000 {
+            let key = format!("k{:05}", i);
+            db.put(key.as_bytes(), &value).unwrap();
+        }
+        db.flush().unwrap();
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+
+        for i in (0..4000).step_by(97) {
+            let key = format!("k{:05}", i);
+            assert_eq!(&*db.get(key.as_bytes()).unwrap().unwrap(), &value[..]);
+        }
+        assert!(db.get(b"k99999").unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_set_inplace_update_support() {
+    let n = DBPath::new("_rust_rocksdb_test_set_inplace_update_support");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_allow_concurrent_memtable_write(false);
+        opts.set_inplace_update_support(true);
+        opts.set_inplace_update_num_locks(100);
+        let db = DB::open(&opts, &n).unwrap();
+        for i in 0..1000 {
+            let value = format!("{:08}", i);
+            db.put(b"k1", value.as_bytes()).unwrap();
+        }
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"00000999");
+        db.flush().unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"00000999");
+    }
+}
+
+#[test]
+fn test_set_max_compaction_bytes() {
+    let n = DBPath::new("_rust_rocksdb_test_set_max_compaction_bytes");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_write_buffer_size(64 * 1024);
+        opts.set_target_file_size_base(64 * 1024);
+        opts.set_max_compaction_bytes(256 * 1024);
+        let db = DB::open(&opts, &n).unwrap();
+        let value = vec![b'x'; 1024];
+        for i in 0..4000 {
+            let key = format!("k{:05}", i);
+            db.put(key.as_bytes(), &value).unwrap();
+        }
+        db.flush().unwrap();
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+
+        assert_eq!(
+            db.property_int_value("rocksdb.num-files-at-level0")
+                .unwrap(),
+            Some(0)
+        );
+        for i in (0..4000).step_by(97) {
+            let key = format!("k{:05}", i);
+            assert_eq!(&*db.get(key.as_bytes()).unwrap().unwrap(), &value[..]);
+        }
+    }
+}
@@ -40,13 +40,13 @@ pub fn test_slice_transform() {
 
         {
             let expected = vec![(cba(&a1), cba(&a1)), (cba(&a2), cba(&a2))];
-            let a_iterator = db.prefix_iterator(b"aaa");
+            let a_iterator = db.prefix_iterator(b"aaa").unwrap();
             assert_eq!(a_iterator.collect::<Vec<_>>(), expected)
         }
 
         {
             let expected = vec![(cba(&b1), cba(&b1)), (cba(&b2), cba(&b2))];
-            let b_iterator = db.prefix_iterator(b"bbb");
+            let b_iterator = db.prefix_iterator(b"bbb").unwrap();
             assert_eq!(b_iterator.collect::<Vec<_>>(), expected)
         }
     }
@@ -0,0 +1,96 @@
+// Copyright 2019 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+mod util;
+
+use rocksdb::{BulkLoader, IngestExternalFileOptions, Options, SstFileWriter, DB};
+use std::fs;
+use std::path::{Path, PathBuf};
+use util::DBPath;
+
+/// Writes the keys `prefix0000..prefix{count}` to a new SST file. The file is
+/// named like an SST file of a database, so `DB::destroy` cleans it up.
+fn write_sst(dir: &Path, number: u32, prefix: &str, count: usize) -> PathBuf {
+    let path = dir.join(format!("{:06}.sst", number));
+    let opts = Options::default();
+    let mut writer = SstFileWriter::create(&opts);
+    writer.open(&path).unwrap();
+    for i in 0..count {
+        let key = format!("{}{:04}", prefix, i);
+        writer.put(key.as_bytes(), b"value").unwrap();
+    }
+    writer.finish().unwrap();
+    path
+}
+
+#[test]
+fn test_ingest_external_file() {
+    let path = DBPath::new("_rust_rocksdb_test_ingest_external_file");
+    let sst_dir = DBPath::new("_rust_rocksdb_test_ingest_external_file_sst");
+    fs::create_dir_all(&sst_dir).unwrap();
+    {
+        let first = write_sst(sst_dir.as_ref(), 1, "a", 10);
+        let second = write_sst(sst_dir.as_ref(), 2, "b", 10);
+
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        db.put(b"a0000", b"old").unwrap();
+
+        db.ingest_external_file(vec![&first]).unwrap();
+        assert_eq!(&*db.get(b"a0000").unwrap().unwrap(), b"value");
+        assert!(first.exists());
+
+        let cf1 = db.cf_handle("cf1").unwrap();
+        let mut ingest_opts = IngestExternalFileOptions::default();
+        ingest_opts.set_move_files(true);
+        db.ingest_external_file_cf_opt(cf1, &ingest_opts, vec![&second])
+            .unwrap();
+        assert_eq!(&*db.get_cf(cf1, b"b0009").unwrap().unwrap(), b"value");
+        assert!(db.get(b"b0009").unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_bulk_loader() {
+    let path = DBPath::new("_rust_rocksdb_test_bulk_loader");
+    let sst_dir = DBPath::new("_rust_rocksdb_test_bulk_loader_sst");
+    fs::create_dir_all(&sst_dir).unwrap();
+    {
+        let files = vec![
+            write_sst(sst_dir.as_ref(), 1, "a", 100),
+            write_sst(sst_dir.as_ref(), 2, "b", 100),
+            write_sst(sst_dir.as_ref(), 3, "c", 100),
+        ];
+
+        let db = BulkLoader::new(&path)
+            .with_options(|opts| opts.set_max_open_files(100))
+            .ingest(files)
+            .finish()
+            .unwrap();
+
+        for prefix in &["a", "b", "c"] {
+            let key = format!("{}0099", prefix);
+            assert_eq!(&*db.get(key.as_bytes()).unwrap().unwrap(), b"value");
+        }
+        assert_eq!(
+            db.property_int_value("rocksdb.num-files-at-level0")
+                .unwrap(),
+            Some(0)
+        );
+        db.put(b"d0000", b"value").unwrap();
+    }
+}
@@ -13,8 +13,12 @@
 // limitations under the License.
 //
 extern crate rocksdb;
+mod util;
 
-use rocksdb::WriteBatch;
+use rocksdb::{IteratorMode, Options, WriteBatch, WriteBatchWithIndex, DB};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use util::DBPath;
 
 #[test]
 fn test_write_batch_clear() {
@@ -25,3 +29,225 @@ fn test_write_batch_clear() {
     assert_eq!(batch.len(), 0);
     assert!(batch.is_empty());
 }
+
+#[test]
+fn test_write_batch_from_data() {
+    let path = DBPath::new("_rust_rocksdb_test_write_batch_from_data");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k3", b"v3").unwrap();
+
+        let mut batch = WriteBatch::default();
+        let _ = batch.put(b"k1", b"v1");
+        let _ = batch.put(b"k2", b"v2");
+        let _ = batch.delete(b"k3");
+
+        let data = batch.data().to_vec();
+        assert_eq!(data.len(), batch.size_in_bytes());
+
+        let replayed = WriteBatch::from_data(&data);
+        assert_eq!(replayed.len(), 3);
+        assert_eq!(replayed.data(), &data[..]);
+
+        db.write(replayed).unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+        assert!(db.get(b"k3").unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_write_batch_put_slice_parts() {
+    let path = DBPath::new("_rust_rocksdb_test_write_batch_put_slice_parts");
+    {
+        let db = DB::open_default(&path).unwrap();
+        let mut batch = WriteBatch::default();
+        batch
+            .put_slice_parts(&[b"user:", b"42"], &[b"first", b"-", b"last"])
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+
+        db.write(batch).unwrap();
+        assert_eq!(&*db.get(b"user:42").unwrap().unwrap(), b"first-last");
+    }
+}
+
+#[test]
+fn test_write_observer() {
+    let path = DBPath::new("_rust_rocksdb_test_write_observer");
+    {
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        // Shared, since the observer is meant to be set on a shared database.
+        let db = DB::open_shared(&opts, &path).unwrap();
+        let sink = observed.clone();
+        db.set_write_observer(move |batch: &WriteBatch| {
+            sink.lock().unwrap().push(batch.data().to_vec());
+        });
+
+        let mut expected = Vec::new();
+        for i in 0..3 {
+            let mut batch = WriteBatch::default();
+            batch.put(format!("k{}", i), b"value").unwrap();
+            batch.delete(b"gone").unwrap();
+            expected.push(batch.data().to_vec());
+            db.write(batch).unwrap();
+        }
+
+        // Single-key updates are reported as batches of one update.
+        db.put(b"k9", b"value").unwrap();
+        let mut batch = WriteBatch::default();
+        batch.put(b"k9", b"value").unwrap();
+        expected.push(batch.data().to_vec());
+
+        db.delete(b"k9").unwrap();
+        let mut batch = WriteBatch::default();
+        batch.delete(b"k9").unwrap();
+        expected.push(batch.data().to_vec());
+
+        let mut indexed = WriteBatchWithIndex::new(0, true);
+        indexed.put(b"k8", b"value").unwrap();
+        db.write_wbwi(&indexed).unwrap();
+        let mut batch = WriteBatch::default();
+        batch.put(b"k8", b"value").unwrap();
+        expected.push(batch.data().to_vec());
+
+        db.clear_write_observer();
+        db.put(b"k7", b"value").unwrap();
+
+        let observed = observed.lock().unwrap();
+        assert_eq!(observed.len(), expected.len());
+        for (data, expected) in observed.iter().zip(&expected) {
+            // The sequence number is only assigned by the write.
+            assert_eq!(&data[12..], &expected[12..]);
+        }
+    }
+}
+
+#[test]
+fn test_write_observer_keeps_write_errors() {
+    let path = DBPath::new("_rust_rocksdb_test_write_observer_keeps_write_errors");
+    {
+        let observed = Arc::new(Mutex::new(0));
+        let db = DB::open_default(&path).unwrap();
+        let sink = observed.clone();
+        db.set_write_observer(move |_: &WriteBatch| *sink.lock().unwrap() += 1);
+
+        // Without a merge operator, RocksDB still rejects single merges.
+        assert!(db.merge(b"counter", b"1").is_err());
+        assert!(db.upsert(b"counter", 1).is_err());
+        assert_eq!(*observed.lock().unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_write_observer_order() {
+    let path = DBPath::new("_rust_rocksdb_test_write_observer_order");
+    {
+        let sequences = Arc::new(Mutex::new(Vec::new()));
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open_shared(&opts, &path).unwrap();
+        let sink = sequences.clone();
+        db.set_write_observer(move |batch: &WriteBatch| {
+            // The write stores the batch's sequence number in its first bytes.
+            let mut sequence = [0; 8];
+            sequence.copy_from_slice(&batch.data()[..8]);
+            sink.lock().unwrap().push(u64::from_le_bytes(sequence));
+        });
+
+        let writers: Vec<_> = (0..4)
+            .map(|t| {
+                let db = db.clone();
+                thread::spawn(move || {
+                    for i in 0..50 {
+                        let mut batch = WriteBatch::default();
+                        batch.put(format!("k{}-{}", t, i), b"value").unwrap();
+                        db.write(batch).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let sequences = sequences.lock().unwrap();
+        assert_eq!(sequences.len(), 200);
+        assert!(sequences.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+#[test]
+fn test_write_batch_with_index() {
+    let path = DBPath::new("_rust_rocksdb_test_write_batch_with_index");
+    {
+        let opts = Options::default();
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+
+        let mut batch = WriteBatchWithIndex::new(0, true);
+        batch.put(b"k1", b"staged").unwrap();
+        batch.delete(b"k2").unwrap();
+        batch.put(b"k3", b"v3").unwrap();
+        assert_eq!(batch.len(), 3);
+
+        assert_eq!(
+            &*batch.get_from_batch(&opts, b"k1").unwrap().unwrap(),
+            b"staged"
+        );
+        assert!(batch.get_from_batch(&opts, b"k4").unwrap().is_none());
+        assert_eq!(
+            &*batch.get_from_batch_and_db(&db, b"k1").unwrap().unwrap(),
+            b"staged"
+        );
+        assert!(batch.get_from_batch_and_db(&db, b"k2").unwrap().is_none());
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k3").unwrap().is_none());
+
+        db.write_wbwi(&batch).unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"staged");
+        assert!(db.get(b"k2").unwrap().is_none());
+        assert_eq!(&*db.get(b"k3").unwrap().unwrap(), b"v3");
+
+        batch.clear().unwrap();
+        assert!(batch.is_empty());
+    }
+}
+
+#[test]
+fn test_write_batch_with_index_iterator_with_base() {
+    let path = DBPath::new("_rust_rocksdb_test_write_batch_with_index_iterator_with_base");
+    {
+        let db = DB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+        db.put(b"k4", b"v4").unwrap();
+
+        let mut batch = WriteBatchWithIndex::new(0, true);
+        batch.put(b"k1", b"staged").unwrap();
+        batch.delete(b"k2").unwrap();
+        batch.put(b"k3", b"v3").unwrap();
+        batch.put(b"k5", b"v5").unwrap();
+
+        let base = db.iterator(IteratorMode::Start).unwrap();
+        let merged: Vec<_> = batch
+            .iterator_with_base(base, IteratorMode::Start)
+            .map(|(key, value)| (key.to_vec(), value.to_vec()))
+            .collect();
+        let expected: Vec<(Vec<u8>, Vec<u8>)> = vec![
+            (b"k1".to_vec(), b"staged".to_vec()),
+            (b"k3".to_vec(), b"v3".to_vec()),
+            (b"k4".to_vec(), b"v4".to_vec()),
+            (b"k5".to_vec(), b"v5".to_vec()),
+        ];
+        assert_eq!(merged, expected);
+
+        let base = db.iterator(IteratorMode::Start).unwrap();
+        let mut reversed = batch.iterator_with_base(base, IteratorMode::End);
+        assert_eq!(&*reversed.next().unwrap().0, b"k5");
+        assert_eq!(&*reversed.next().unwrap().0, b"k4");
+    }
+}